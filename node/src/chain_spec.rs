@@ -1,12 +1,12 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, KittiesConfig, KittiesPalletId,
+	Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{sr25519, Pair, Public};
+use sp_core::{blake2_128, sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::traits::{AccountIdConversion, IdentifyAccount, Verify};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -122,6 +122,17 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 	))
 }
 
+/// A few kitties for Alice and Bob, their dna is derived from the owner's seed.
+fn initial_kitties() -> Vec<(AccountId, [u8; 16])> {
+	["Alice", "Bob"]
+		.iter()
+		.flat_map(|seed| {
+			let owner = get_account_id_from_seed::<sr25519::Public>(seed);
+			(0..3).map(move |i| (owner.clone(), blake2_128(format!("{}/{}", seed, i).as_bytes())))
+		})
+		.collect()
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
@@ -137,8 +148,14 @@ fn testnet_genesis(
 			changes_trie_config: Default::default(),
 		},
 		balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+			// Configure endowed accounts with initial balance of 1 << 60, and fund the kitty
+			// staking pot that rewards are paid from.
+			balances: endowed_accounts
+				.iter()
+				.cloned()
+				.map(|k| (k, 1 << 60))
+				.chain(std::iter::once((KittiesPalletId::get().into_account(), 1 << 50)))
+				.collect(),
 		},
 		aura: AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -150,5 +167,9 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			key: root_key,
 		},
+		kitties: KittiesConfig {
+			// Start with kitties to play with.
+			kitties: initial_kitties(),
+		},
 	}
 }
@@ -0,0 +1,35 @@
+//! Runtime API definition for querying pallet-kitties.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_kitties::{genes::KittyTraits, KittyInfo, KittyMetadataInfo, KittySale, ListingSort};
+
+sp_api::decl_runtime_apis! {
+	/// Version 2 adds the queries of single kitties, owners and the kitty count, version 3 the
+	/// kitty metadata.
+	#[api_version(3)]
+	pub trait KittiesApi<AccountId, KittyIndex, Balance, BlockNumber> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// A page of the kitties for sale, sorted by `sort`.
+		fn kitties_for_sale(start: u32, limit: u32, sort: ListingSort) -> Vec<KittySale<AccountId, KittyIndex, Balance, BlockNumber>>;
+		/// A kitty with its owner and traits.
+		fn kitty(kitty_id: KittyIndex) -> Option<KittyInfo<AccountId, KittyIndex, BlockNumber>>;
+		/// The kitties of `owner`.
+		fn kitties_of(owner: AccountId) -> Vec<KittyInfo<AccountId, KittyIndex, BlockNumber>>;
+		/// The name and uri of a kitty, `None` if it has no metadata.
+		fn kitty_metadata(kitty_id: KittyIndex) -> Option<KittyMetadataInfo>;
+		/// The traits expressed by the dna of a kitty.
+		fn kitty_traits(kitty_id: KittyIndex) -> Option<KittyTraits>;
+		/// The sale of a kitty, `None` if it is not for sale.
+		fn listing(kitty_id: KittyIndex) -> Option<KittySale<AccountId, KittyIndex, Balance, BlockNumber>>;
+		/// The number of existing kitties.
+		fn kitties_count() -> u32;
+	}
+}
@@ -0,0 +1,390 @@
+//! RPC interface for pallet-kitties.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_rpc_runtime_api::{KittiesApi as KittiesRuntimeApi, KittyInfo, KittyMetadataInfo, KittySale, KittyTraits, ListingSort};
+
+/// Error code of a failed runtime api call.
+const RUNTIME_ERROR: i64 = 1;
+
+/// A kitty as served over RPC, its dna as a hex string.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RpcKittyInfo<AccountId, KittyIndex, BlockNumber> {
+	pub kitty_id: KittyIndex,
+	pub owner: AccountId,
+	pub dna: Bytes,
+	pub parents: Option<(KittyIndex, KittyIndex)>,
+	pub generation: u16,
+	pub next_breed_block: BlockNumber,
+	pub creator: Option<AccountId>,
+	pub traits: KittyTraits,
+}
+
+impl<AccountId, KittyIndex, BlockNumber> From<KittyInfo<AccountId, KittyIndex, BlockNumber>> for RpcKittyInfo<AccountId, KittyIndex, BlockNumber> {
+	fn from(kitty: KittyInfo<AccountId, KittyIndex, BlockNumber>) -> Self {
+		RpcKittyInfo {
+			kitty_id: kitty.kitty_id,
+			owner: kitty.owner,
+			dna: kitty.dna.to_vec().into(),
+			parents: kitty.parents,
+			generation: kitty.generation,
+			next_breed_block: kitty.next_breed_block,
+			creator: kitty.creator,
+			traits: kitty.traits,
+		}
+	}
+}
+
+/// A sale as served over RPC. The price is a `NumberOrHex`, so JavaScript clients do not lose
+/// the precision of large balances.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RpcKittySale<AccountId, KittyIndex, BlockNumber> {
+	pub kitty_id: KittyIndex,
+	pub seller: AccountId,
+	pub price: NumberOrHex,
+	pub listed_at: BlockNumber,
+	pub ends_at: Option<BlockNumber>,
+}
+
+impl<AccountId, KittyIndex, Balance: Into<NumberOrHex>, BlockNumber> From<KittySale<AccountId, KittyIndex, Balance, BlockNumber>>
+	for RpcKittySale<AccountId, KittyIndex, BlockNumber>
+{
+	fn from(sale: KittySale<AccountId, KittyIndex, Balance, BlockNumber>) -> Self {
+		RpcKittySale {
+			kitty_id: sale.kitty_id,
+			seller: sale.seller,
+			price: sale.price.into(),
+			listed_at: sale.listed_at,
+			ends_at: sale.ends_at,
+		}
+	}
+}
+
+/// The metadata of a kitty as served over RPC, as hex strings.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RpcKittyMetadata {
+	pub name: Bytes,
+	pub uri: Bytes,
+}
+
+impl From<KittyMetadataInfo> for RpcKittyMetadata {
+	fn from(metadata: KittyMetadataInfo) -> Self {
+		RpcKittyMetadata { name: metadata.name.into(), uri: metadata.uri.into() }
+	}
+}
+
+#[rpc]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex, Balance, BlockNumber> {
+	/// A page of the kitties for sale at block `at`, or the best block, sorted by `sort`.
+	#[rpc(name = "kitties_forSale")]
+	fn kitties_for_sale(
+		&self,
+		start: u32,
+		limit: u32,
+		sort: ListingSort,
+		at: Option<BlockHash>,
+	) -> Result<Vec<RpcKittySale<AccountId, KittyIndex, BlockNumber>>>;
+
+	/// A kitty with its owner and traits.
+	#[rpc(name = "kitties_kitty")]
+	fn kitty(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Option<RpcKittyInfo<AccountId, KittyIndex, BlockNumber>>>;
+
+	/// The kitties of `owner`.
+	#[rpc(name = "kitties_ofOwner")]
+	fn kitties_of(&self, owner: AccountId, at: Option<BlockHash>) -> Result<Vec<RpcKittyInfo<AccountId, KittyIndex, BlockNumber>>>;
+
+	/// The name and uri of a kitty, `null` if it has no metadata.
+	#[rpc(name = "kitties_metadata")]
+	fn kitty_metadata(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Option<RpcKittyMetadata>>;
+
+	/// The traits expressed by the dna of a kitty.
+	#[rpc(name = "kitties_traits")]
+	fn kitty_traits(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Option<KittyTraits>>;
+
+	/// The sale of a kitty, `null` if it is not for sale.
+	#[rpc(name = "kitties_listing")]
+	fn listing(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Option<RpcKittySale<AccountId, KittyIndex, BlockNumber>>>;
+
+	/// The number of existing kitties.
+	#[rpc(name = "kitties_count")]
+	fn kitties_count(&self, at: Option<BlockHash>) -> Result<u32>;
+}
+
+/// Serves the kitties RPC methods from the runtime api.
+pub struct Kitties<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Kitties<C, Block> {
+	pub fn new(client: Arc<C>) -> Self {
+		Kitties { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> Kitties<C, Block>
+where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	/// The queried block, the best one if `at` is `None`.
+	fn block(&self, at: Option<<Block as BlockT>::Hash>) -> BlockId<Block> {
+		BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Display) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: message.into(),
+		data: Some(e.to_string().into()),
+	}
+}
+
+impl<C, Block, AccountId, KittyIndex, Balance, BlockNumber> KittiesApi<<Block as BlockT>::Hash, AccountId, KittyIndex, Balance, BlockNumber>
+	for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex, Balance, BlockNumber>,
+	AccountId: Codec,
+	KittyIndex: Codec,
+	Balance: Codec + Into<NumberOrHex>,
+	BlockNumber: Codec,
+{
+	fn kitties_for_sale(
+		&self,
+		start: u32,
+		limit: u32,
+		sort: ListingSort,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RpcKittySale<AccountId, KittyIndex, BlockNumber>>> {
+		self.client.runtime_api().kitties_for_sale(&self.block(at), start, limit, sort)
+			.map(|sales| sales.into_iter().map(Into::into).collect())
+			.map_err(|e| runtime_error("Unable to query kitties for sale.", e))
+	}
+
+	fn kitty(&self, kitty_id: KittyIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Option<RpcKittyInfo<AccountId, KittyIndex, BlockNumber>>> {
+		self.client.runtime_api().kitty(&self.block(at), kitty_id)
+			.map(|kitty| kitty.map(Into::into))
+			.map_err(|e| runtime_error("Unable to query kitty.", e))
+	}
+
+	fn kitties_of(&self, owner: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<RpcKittyInfo<AccountId, KittyIndex, BlockNumber>>> {
+		self.client.runtime_api().kitties_of(&self.block(at), owner)
+			.map(|kitties| kitties.into_iter().map(Into::into).collect())
+			.map_err(|e| runtime_error("Unable to query kitties of owner.", e))
+	}
+
+	fn kitty_metadata(&self, kitty_id: KittyIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Option<RpcKittyMetadata>> {
+		self.client.runtime_api().kitty_metadata(&self.block(at), kitty_id)
+			.map(|metadata| metadata.map(Into::into))
+			.map_err(|e| runtime_error("Unable to query kitty metadata.", e))
+	}
+
+	fn kitty_traits(&self, kitty_id: KittyIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Option<KittyTraits>> {
+		self.client.runtime_api().kitty_traits(&self.block(at), kitty_id)
+			.map_err(|e| runtime_error("Unable to query kitty traits.", e))
+	}
+
+	fn listing(&self, kitty_id: KittyIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Option<RpcKittySale<AccountId, KittyIndex, BlockNumber>>> {
+		self.client.runtime_api().listing(&self.block(at), kitty_id)
+			.map(|sale| sale.map(Into::into))
+			.map_err(|e| runtime_error("Unable to query kitty listing.", e))
+	}
+
+	fn kitties_count(&self, at: Option<<Block as BlockT>::Hash>) -> Result<u32> {
+		self.client.runtime_api().kitties_count(&self.block(at))
+			.map_err(|e| runtime_error("Unable to query kitties count.", e))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpc_core::IoHandler;
+	use sp_api::{ApiRef, ProvideRuntimeApi};
+	use sp_blockchain::{BlockStatus, Info};
+	use sp_runtime::{
+		generic::BlockId,
+		testing::{Block as TestBlock, ExtrinsicWrapper, Header, H256},
+		traits::NumberFor,
+	};
+
+	type Block = TestBlock<ExtrinsicWrapper<u64>>;
+
+	/// A client whose runtime knows kitty 1 of account 7, listed for 100. Its best block is the
+	/// zero hash.
+	struct TestClient;
+
+	struct TestRuntimeApi;
+
+	fn kitty_info() -> KittyInfo<u64, u32, u64> {
+		let dna = [0; 16];
+		KittyInfo {
+			kitty_id: 1,
+			owner: 7,
+			dna,
+			parents: None,
+			generation: 0,
+			next_breed_block: 5,
+			creator: Some(7),
+			traits: pallet_kitties::genes::dna_traits(&dna),
+		}
+	}
+
+	fn kitty_sale() -> KittySale<u64, u32, u64, u64> {
+		KittySale { kitty_id: 1, seller: 7, price: 100, listed_at: 3, ends_at: None }
+	}
+
+	sp_api::mock_impl_runtime_apis! {
+		impl KittiesRuntimeApi<Block, u64, u32, u64, u64> for TestRuntimeApi {
+			fn kitties_for_sale(_start: u32, limit: u32, _sort: ListingSort) -> Vec<KittySale<u64, u32, u64, u64>> {
+				vec![kitty_sale()].into_iter().take(limit as usize).collect()
+			}
+
+			fn kitty(kitty_id: u32) -> Option<KittyInfo<u64, u32, u64>> {
+				Some(kitty_info()).filter(|_| kitty_id == 1)
+			}
+
+			fn kitties_of(owner: u64) -> Vec<KittyInfo<u64, u32, u64>> {
+				Some(kitty_info()).filter(|_| owner == 7).into_iter().collect()
+			}
+
+			fn kitty_metadata(kitty_id: u32) -> Option<KittyMetadataInfo> {
+				Some(KittyMetadataInfo { name: b"Tom".to_vec(), uri: b"ipfs://tom".to_vec() }).filter(|_| kitty_id == 1)
+			}
+
+			fn kitty_traits(kitty_id: u32) -> Option<KittyTraits> {
+				Some(kitty_info().traits).filter(|_| kitty_id == 1)
+			}
+
+			fn listing(kitty_id: u32) -> Option<KittySale<u64, u32, u64, u64>> {
+				Some(kitty_sale()).filter(|_| kitty_id == 1)
+			}
+
+			fn kitties_count() -> u32 {
+				1
+			}
+		}
+	}
+
+	impl ProvideRuntimeApi<Block> for TestClient {
+		type Api = TestRuntimeApi;
+
+		fn runtime_api<'a>(&'a self) -> ApiRef<'a, Self::Api> {
+			TestRuntimeApi.into()
+		}
+	}
+
+	impl HeaderBackend<Block> for TestClient {
+		fn header(&self, _id: BlockId<Block>) -> sp_blockchain::Result<Option<Header>> {
+			Ok(None)
+		}
+
+		fn info(&self) -> Info<Block> {
+			Info {
+				best_hash: H256::zero(),
+				best_number: 0,
+				genesis_hash: H256::zero(),
+				finalized_hash: H256::zero(),
+				finalized_number: 0,
+				finalized_state: None,
+				number_leaves: 1,
+			}
+		}
+
+		fn status(&self, _id: BlockId<Block>) -> sp_blockchain::Result<BlockStatus> {
+			Ok(BlockStatus::InChain)
+		}
+
+		fn number(&self, _hash: H256) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+			Ok(None)
+		}
+
+		fn hash(&self, _number: NumberFor<Block>) -> sp_blockchain::Result<Option<H256>> {
+			Ok(None)
+		}
+	}
+
+	fn io() -> IoHandler {
+		let mut io = IoHandler::default();
+		io.extend_with(KittiesApi::to_delegate(Kitties::<TestClient, Block>::new(Arc::new(TestClient))));
+		io
+	}
+
+	/// The result of calling `method` with `params` at the zero block hash.
+	fn call(method: &str, params: &str) -> jsonrpc_core::serde_json::Value {
+		let at = format!("\"{:?}\"", H256::zero());
+		call_raw(method, &if params.is_empty() { at } else { format!("{}, {}", params, at) })
+	}
+
+	/// The result of calling `method` with exactly `params`.
+	fn call_raw(method: &str, params: &str) -> jsonrpc_core::serde_json::Value {
+		let request = format!(r#"{{"jsonrpc":"2.0","method":"{}","params":[{}],"id":1}}"#, method, params);
+		let response = io().handle_request_sync(&request).expect("every call has a response");
+		let response: jsonrpc_core::serde_json::Value = jsonrpc_core::serde_json::from_str(&response).unwrap();
+		response["result"].clone()
+	}
+
+	#[test]
+	fn kitty_is_served_as_json() {
+		let kitty = call("kitties_kitty", "1");
+		assert_eq!(kitty["owner"], 7);
+		assert_eq!(kitty["generation"], 0);
+		assert_eq!(kitty["creator"], 7);
+		assert_eq!(kitty["traits"]["body_color"], "Black");
+		assert_eq!(kitty["traits"]["rarity"], "Common");
+		assert_eq!(kitty["dna"], format!("0x{}", "00".repeat(16)));
+
+		assert!(call("kitties_kitty", "2").is_null());
+	}
+
+	#[test]
+	fn kitties_of_owner_are_served() {
+		let kitties = call("kitties_ofOwner", "7");
+		assert_eq!(kitties.as_array().unwrap().len(), 1);
+		assert_eq!(kitties[0]["kitty_id"], 1);
+		assert_eq!(call("kitties_ofOwner", "8").as_array().unwrap().len(), 0);
+	}
+
+	#[test]
+	fn metadata_is_served() {
+		let metadata = call("kitties_metadata", "1");
+		assert_eq!(metadata["name"], "0x546f6d");
+		assert!(call("kitties_metadata", "2").is_null());
+	}
+
+	#[test]
+	fn best_block_is_queried_without_at() {
+		assert_eq!(call_raw("kitties_kitty", "1")["owner"], 7);
+		assert_eq!(call_raw("kitties_count", ""), 1);
+		assert_eq!(call_raw("kitties_listing", "1, null")["price"], 100);
+	}
+
+	#[test]
+	fn large_prices_are_served_as_hex() {
+		let sale = RpcKittySale::<u64, u32, u64>::from(KittySale { kitty_id: 1, seller: 7, price: u128::MAX, listed_at: 3, ends_at: None });
+		let sale = jsonrpc_core::serde_json::to_value(sale).unwrap();
+		assert_eq!(sale["price"], format!("0x{}", "f".repeat(32)));
+	}
+
+	#[test]
+	fn traits_listing_and_count_are_served() {
+		assert_eq!(call("kitties_traits", "1")["pattern"], "Solid");
+		assert_eq!(call("kitties_listing", "1")["price"], 100);
+		assert!(call("kitties_listing", "2").is_null());
+		assert_eq!(call("kitties_forSale", "0, 10, \"PriceAsc\"")[0]["seller"], 7);
+		assert_eq!(call("kitties_count", ""), 1);
+	}
+}
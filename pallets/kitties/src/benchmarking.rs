@@ -0,0 +1,611 @@
+//! Benchmarking setup for pallet-kitties
+//! Need Build with: cargo build --release --features runtime-benchmarks
+//! Need Test with: cargo test -p pallet-kitties --features runtime-benchmarks
+//! Need Make weights.rs with: ./target/release/node-template benchmark --chain dev --execution=wasm --wasm-execution=compiled --pallet pallet_kitties --extrinsic '*' --steps 20 --repeat 50 --template=.maintain/frame-weight-template.hbs --output=./pallets/kitties/src/weights.rs
+//!
+//! Every call runs at its worst case: the accounts involved own up to `MaxKittiesOwned` kitties,
+//! the per block queues are one short of full and the kitty carries metadata and a listing.
+//! The `on_initialize_*` benchmarks settle each per block queue on its own, up to its limit.
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Kitties;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::{Currency, Get, Hooks};
+use frame_system::{EventRecord, RawOrigin};
+use sp_runtime::traits::{Bounded, Hash, Saturating};
+use sp_std::{convert::TryInto, prelude::*};
+
+const SEED: u32 = 0;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	let events = frame_system::Pallet::<T>::events();
+	let system_event: <T as frame_system::Config>::Event = generic_event.into();
+	let EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+fn fund<T: Config>(who: &T::AccountId) {
+	T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 4u32.into());
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who = account(name, index, SEED);
+	fund::<T>(&who);
+	who
+}
+
+/// Give `owner` `n` new kitties, returns their ids.
+fn create_kitties<T: Config>(owner: &T::AccountId, n: u32) -> Vec<T::KittyIndex> {
+	(0..n).map(|_| Kitties::<T>::do_create(owner).expect("owner is funded")).collect()
+}
+
+/// Store the largest metadata a kitty can have.
+fn set_full_metadata<T: Config>(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+	let name = vec![b'n'; T::MaxNameLength::get() as usize];
+	let uri = vec![b'u'; T::MaxUriLength::get() as usize];
+	Kitties::<T>::set_metadata(RawOrigin::Signed(owner.clone()).into(), kitty_id, name, uri).expect("owner is funded");
+}
+
+/// Kitty ids filling a per block queue up to one short of `L`.
+fn fill_ends<T: Config, L: Get<u32>>() -> BoundedVec<T::KittyIndex, L> {
+	let ids: Vec<T::KittyIndex> = (0..L::get().saturating_sub(1)).map(|i| T::KittyIndex::from(u32::MAX - i)).collect();
+	ids.try_into().expect("below the limit")
+}
+
+/// Fill the breeding commits lapsing at `expiry` up to one short of `MaxBreedCommitsPerBlock`.
+fn fill_breed_commit_expiries<T: Config>(expiry: T::BlockNumber) {
+	let lapsing: Vec<T::AccountId> = (0..T::MaxBreedCommitsPerBlock::get().saturating_sub(1))
+		.map(|i| account("lapsing", i, SEED))
+		.collect();
+	let lapsing: BoundedVec<_, T::MaxBreedCommitsPerBlock> = lapsing.try_into().expect("below the limit");
+	BreedCommitExpiries::<T>::insert(expiry, lapsing);
+}
+
+/// Fill the offers lapsing at `expiry` up to one short of `MaxOffersPerBlock`.
+fn fill_offer_expiries<T: Config>(expiry: T::BlockNumber) {
+	let lapsing: Vec<(T::KittyIndex, T::AccountId)> = (0..T::MaxOffersPerBlock::get().saturating_sub(1))
+		.map(|i| (T::KittyIndex::from(u32::MAX - i), account("lapsing", i, SEED)))
+		.collect();
+	let lapsing: BoundedVec<_, T::MaxOffersPerBlock> = lapsing.try_into().expect("below the limit");
+	OfferExpiries::<T>::insert(expiry, lapsing);
+}
+
+benchmarks! {
+	create {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		create_kitties::<T>(&caller, T::MaxKittiesOwned::get() - 1);
+		let kitty_id = Kitties::<T>::next_kitty_id();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Owner::<T>::get(kitty_id), Some(caller));
+	}
+
+	force_create {
+		let owner = funded_account::<T>("owner", 0);
+		create_kitties::<T>(&owner, T::MaxKittiesOwned::get() - 1);
+		let kitty_id = Kitties::<T>::next_kitty_id();
+	}: _(RawOrigin::Root, owner.clone(), true)
+	verify {
+		assert_eq!(Owner::<T>::get(kitty_id), Some(owner));
+		assert!(Locked::<T>::get(kitty_id));
+	}
+
+	lock {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		Kitties::<T>::to_sell(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller), kitty_id)
+	verify {
+		assert!(Locked::<T>::get(kitty_id));
+	}
+
+	stake_kitty {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		Kitties::<T>::to_sell(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::KittyStaked(caller, kitty_id).into());
+	}
+
+	unstake_kitty {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		fund::<T>(&Kitties::<T>::account_id());
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		Kitties::<T>::stake_kitty(RawOrigin::Signed(caller.clone()).into(), kitty_id)?;
+		frame_system::Pallet::<T>::set_block_number(100u32.into());
+	}: _(RawOrigin::Signed(caller), kitty_id)
+	verify {
+		assert!(!Stakes::<T>::contains_key(kitty_id));
+	}
+
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = *create_kitties::<T>(&caller, T::MaxKittiesOwned::get()).last().unwrap();
+		set_full_metadata::<T>(&caller, kitty_id);
+		Kitties::<T>::to_sell(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into())?;
+		let to = funded_account::<T>("to", 0);
+		create_kitties::<T>(&to, T::MaxKittiesOwned::get() - 1);
+	}: _(RawOrigin::Signed(caller.clone()), to.clone(), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::KittyTransfer(caller, to, kitty_id).into());
+	}
+
+	transfer_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_ids = create_kitties::<T>(&caller, T::MaxKittiesOwned::get());
+		let kitty_ids: Vec<T::KittyIndex> = kitty_ids.into_iter().rev().take(n as usize).collect();
+		let to = funded_account::<T>("to", 0);
+		create_kitties::<T>(&to, T::MaxKittiesOwned::get() - n);
+	}: _(RawOrigin::Signed(caller.clone()), to.clone(), kitty_ids.clone())
+	verify {
+		for kitty_id in kitty_ids {
+			assert_eq!(Owner::<T>::get(kitty_id), Some(to.clone()));
+		}
+	}
+
+	approve {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		let spender: T::AccountId = account("spender", 0, SEED);
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, Some(spender.clone()))
+	verify {
+		assert_eq!(Approvals::<T>::get(kitty_id), Some(spender));
+	}
+
+	set_approval_for_all {
+		let caller: T::AccountId = whitelisted_caller();
+		let operator: T::AccountId = account("operator", 0, SEED);
+	}: _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+	verify {
+		assert!(OperatorApprovals::<T>::get(&caller, &operator));
+	}
+
+	// The caller is an operator, the last account `is_approved_or_owner` checks.
+	transfer_from {
+		let caller: T::AccountId = whitelisted_caller();
+		let from = funded_account::<T>("from", 0);
+		let kitty_id = *create_kitties::<T>(&from, T::MaxKittiesOwned::get()).last().unwrap();
+		set_full_metadata::<T>(&from, kitty_id);
+		Kitties::<T>::set_approval_for_all(RawOrigin::Signed(from.clone()).into(), caller.clone(), true)?;
+		let to = funded_account::<T>("to", 0);
+		create_kitties::<T>(&to, T::MaxKittiesOwned::get() - 1);
+	}: _(RawOrigin::Signed(caller), from.clone(), to.clone(), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::KittyTransfer(from, to, kitty_id).into());
+	}
+
+	set_metadata {
+		let n in 1 .. T::MaxNameLength::get();
+		let u in 1 .. T::MaxUriLength::get();
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, vec![b'n'; n as usize], vec![b'u'; u as usize])
+	verify {
+		assert!(Metadata::<T>::contains_key(kitty_id));
+	}
+
+	create_collection {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let collection_id = Kitties::<T>::next_collection_id();
+	}: _(RawOrigin::Signed(caller.clone()), 10, 100u32.into())
+	verify {
+		assert_last_event::<T>(Event::CollectionCreated(caller, collection_id, 10, 100u32.into()).into());
+	}
+
+	// The minter is not the issuer and pays the mint price.
+	mint {
+		let issuer = funded_account::<T>("issuer", 0);
+		let collection_id = Kitties::<T>::next_collection_id();
+		Kitties::<T>::create_collection(RawOrigin::Signed(issuer).into(), 10, 100u32.into())?;
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		create_kitties::<T>(&caller, T::MaxKittiesOwned::get() - 1);
+		let kitty_id = Kitties::<T>::next_kitty_id();
+	}: _(RawOrigin::Signed(caller.clone()), collection_id)
+	verify {
+		assert_eq!(Owner::<T>::get(kitty_id), Some(caller));
+		assert!(CollectionKitties::<T>::contains_key(collection_id, kitty_id));
+	}
+
+	set_collection_metadata {
+		let n in 1 .. T::MaxNameLength::get();
+		let u in 1 .. T::MaxUriLength::get();
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let collection_id = Kitties::<T>::next_collection_id();
+		Kitties::<T>::create_collection(RawOrigin::Signed(caller.clone()).into(), 10, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller), collection_id, vec![b'n'; n as usize], vec![b'u'; u as usize])
+	verify {
+		assert!(CollectionMetadata::<T>::contains_key(collection_id));
+	}
+
+	// The collection has metadata to release as well.
+	destroy_collection {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let collection_id = Kitties::<T>::next_collection_id();
+		Kitties::<T>::create_collection(RawOrigin::Signed(caller.clone()).into(), 10, 100u32.into())?;
+		let name = vec![b'n'; T::MaxNameLength::get() as usize];
+		let uri = vec![b'u'; T::MaxUriLength::get() as usize];
+		Kitties::<T>::set_collection_metadata(RawOrigin::Signed(caller.clone()).into(), collection_id, name, uri)?;
+	}: _(RawOrigin::Signed(caller.clone()), collection_id)
+	verify {
+		assert_last_event::<T>(Event::CollectionDestroyed(caller, collection_id).into());
+	}
+
+	// Burning releases every open offer on the kitty.
+	burn {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = *create_kitties::<T>(&caller, T::MaxKittiesOwned::get()).last().unwrap();
+		set_full_metadata::<T>(&caller, kitty_id);
+		Kitties::<T>::to_sell(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into())?;
+		let expiry = frame_system::Pallet::<T>::block_number().saturating_add(10u32.into());
+		for i in 0 .. T::MaxOffersPerBlock::get() {
+			let bidder = funded_account::<T>("bidder", i);
+			Kitties::<T>::make_offer(RawOrigin::Signed(bidder).into(), kitty_id, T::MinOfferAmount::get(), expiry)?;
+		}
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::KittyBurned(caller, kitty_id).into());
+	}
+
+	bread {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitties = create_kitties::<T>(&caller, T::MaxKittiesOwned::get() - 1);
+		let kitty_id = Kitties::<T>::next_kitty_id();
+	}: _(RawOrigin::Signed(caller.clone()), kitties[0], kitties[1])
+	verify {
+		assert_eq!(Owner::<T>::get(kitty_id), Some(caller));
+	}
+
+	commit_breed {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitties = create_kitties::<T>(&caller, 2);
+		let expiry = frame_system::Pallet::<T>::block_number()
+			.saturating_add(T::RevealDelay::get())
+			.saturating_add(T::RevealTimeout::get());
+		fill_breed_commit_expiries::<T>(expiry);
+		let salt_hash = T::Hashing::hash_of(&[1u8; 32]);
+	}: _(RawOrigin::Signed(caller.clone()), kitties[0], kitties[1], salt_hash)
+	verify {
+		assert!(BreedCommits::<T>::contains_key(&caller));
+	}
+
+	reveal_breed {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitties = create_kitties::<T>(&caller, T::MaxKittiesOwned::get() - 1);
+		let now = frame_system::Pallet::<T>::block_number();
+		fill_breed_commit_expiries::<T>(now.saturating_add(T::RevealDelay::get()).saturating_add(T::RevealTimeout::get()));
+		let salt = [1u8; 32];
+		Kitties::<T>::commit_breed(RawOrigin::Signed(caller.clone()).into(), kitties[0], kitties[1], T::Hashing::hash_of(&salt))?;
+		frame_system::Pallet::<T>::set_block_number(now.saturating_add(T::RevealDelay::get()));
+		let kitty_id = Kitties::<T>::next_kitty_id();
+	}: _(RawOrigin::Signed(caller.clone()), salt)
+	verify {
+		assert_eq!(Owner::<T>::get(kitty_id), Some(caller));
+	}
+
+	list_sire {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, 100u32.into())
+	verify {
+		assert!(Sires::<T>::contains_key(kitty_id));
+	}
+
+	cancel_sire {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		Kitties::<T>::list_sire(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert!(!Sires::<T>::contains_key(kitty_id));
+	}
+
+	breed_with_sire {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let my_kitty = create_kitties::<T>(&caller, T::MaxKittiesOwned::get() - 1)[0];
+		let sire_owner = funded_account::<T>("sire_owner", 0);
+		let sire_kitty = create_kitties::<T>(&sire_owner, 1)[0];
+		Kitties::<T>::list_sire(RawOrigin::Signed(sire_owner).into(), sire_kitty, 100u32.into())?;
+		let kitty_id = Kitties::<T>::next_kitty_id();
+	}: _(RawOrigin::Signed(caller.clone()), my_kitty, sire_kitty, 100u32.into())
+	verify {
+		assert_eq!(Owner::<T>::get(kitty_id), Some(caller));
+	}
+
+	offer_lease {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, 1u32.into(), 10u32.into())
+	verify {
+		assert!(LeaseOffers::<T>::contains_key(kitty_id));
+	}
+
+	cancel_lease_offer {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		Kitties::<T>::offer_lease(RawOrigin::Signed(caller.clone()).into(), kitty_id, 1u32.into(), 10u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert!(!LeaseOffers::<T>::contains_key(kitty_id));
+	}
+
+	take_lease {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitties::<T>(&owner, 1)[0];
+		let duration: T::BlockNumber = 10u32.into();
+		Kitties::<T>::offer_lease(RawOrigin::Signed(owner).into(), kitty_id, 1u32.into(), duration)?;
+		let end = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+		LeaseEnds::<T>::insert(end, fill_ends::<T, T::MaxLeasesPerBlock>());
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, duration)
+	verify {
+		assert_eq!(Kitties::<T>::user_of(kitty_id), Some(caller));
+	}
+
+	// A dutch listing is replaced, it leaves a full expiry queue.
+	to_sell {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		let expiry = frame_system::Pallet::<T>::block_number().saturating_add(11u32.into());
+		DutchListingEnds::<T>::insert(expiry, fill_ends::<T, T::MaxAuctionsPerBlock>());
+		Kitties::<T>::to_sell_dutch(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into(), 10u32.into(), 10u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, 100u32.into())
+	verify {
+		assert!(Listings::<T>::contains_key(kitty_id));
+	}
+
+	list_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let listings: Vec<(T::KittyIndex, BalanceOf<T>)> = create_kitties::<T>(&caller, n)
+			.into_iter()
+			.map(|kitty_id| (kitty_id, 100u32.into()))
+			.collect();
+	}: _(RawOrigin::Signed(caller.clone()), listings.clone())
+	verify {
+		for (kitty_id, _) in listings {
+			assert!(Listings::<T>::contains_key(kitty_id));
+		}
+	}
+
+	// An earlier dutch listing expiring in another block is replaced.
+	to_sell_dutch {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		Kitties::<T>::to_sell_dutch(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into(), 10u32.into(), 5u32.into())?;
+		let expiry = frame_system::Pallet::<T>::block_number().saturating_add(11u32.into());
+		DutchListingEnds::<T>::insert(expiry, fill_ends::<T, T::MaxAuctionsPerBlock>());
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, 100u32.into(), 10u32.into(), 10u32.into())
+	verify {
+		assert!(Listings::<T>::contains_key(kitty_id));
+	}
+
+	cancel_sell {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		let expiry = frame_system::Pallet::<T>::block_number().saturating_add(11u32.into());
+		DutchListingEnds::<T>::insert(expiry, fill_ends::<T, T::MaxAuctionsPerBlock>());
+		Kitties::<T>::to_sell_dutch(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into(), 10u32.into(), 10u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert!(!Listings::<T>::contains_key(kitty_id));
+	}
+
+	// The kitty is resold, so its creator earns a royalty.
+	to_buy {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		create_kitties::<T>(&caller, T::MaxKittiesOwned::get() - 1);
+		let creator = funded_account::<T>("creator", 0);
+		let seller = funded_account::<T>("seller", 0);
+		let kitty_id = create_kitties::<T>(&creator, 1)[0];
+		Kitties::<T>::transfer(RawOrigin::Signed(creator).into(), seller.clone(), kitty_id)?;
+		create_kitties::<T>(&seller, T::MaxKittiesOwned::get() - 1);
+		set_full_metadata::<T>(&seller, kitty_id);
+		Kitties::<T>::to_sell(RawOrigin::Signed(seller.clone()).into(), kitty_id, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, 100u32.into())
+	verify {
+		assert_last_event::<T>(Event::MakeDeal(caller, seller, kitty_id).into());
+	}
+
+	make_offer {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitties::<T>(&owner, 1)[0];
+		let expiry = frame_system::Pallet::<T>::block_number().saturating_add(10u32.into());
+		fill_offer_expiries::<T>(expiry);
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, T::MinOfferAmount::get(), expiry)
+	verify {
+		assert!(Offers::<T>::contains_key(kitty_id, &caller));
+	}
+
+	withdraw_offer {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitties::<T>(&owner, 1)[0];
+		let expiry = frame_system::Pallet::<T>::block_number().saturating_add(10u32.into());
+		fill_offer_expiries::<T>(expiry);
+		Kitties::<T>::make_offer(RawOrigin::Signed(caller.clone()).into(), kitty_id, T::MinOfferAmount::get(), expiry)?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert!(!Offers::<T>::contains_key(kitty_id, &caller));
+	}
+
+	// The kitty is resold, so its creator earns a royalty.
+	accept_offer {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let creator = funded_account::<T>("creator", 0);
+		let kitty_id = create_kitties::<T>(&creator, 1)[0];
+		Kitties::<T>::transfer(RawOrigin::Signed(creator).into(), caller.clone(), kitty_id)?;
+		create_kitties::<T>(&caller, T::MaxKittiesOwned::get() - 1);
+		set_full_metadata::<T>(&caller, kitty_id);
+		let bidder = funded_account::<T>("bidder", 0);
+		create_kitties::<T>(&bidder, T::MaxKittiesOwned::get() - 1);
+		let expiry = frame_system::Pallet::<T>::block_number().saturating_add(10u32.into());
+		fill_offer_expiries::<T>(expiry);
+		Kitties::<T>::make_offer(RawOrigin::Signed(bidder.clone()).into(), kitty_id, T::MinOfferAmount::get(), expiry)?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, bidder.clone())
+	verify {
+		assert_last_event::<T>(Event::OfferAccepted(caller, bidder, kitty_id, T::MinOfferAmount::get()).into());
+	}
+
+	create_auction {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		let duration: T::BlockNumber = 10u32.into();
+		let end = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+		AuctionEnds::<T>::insert(end, fill_ends::<T, T::MaxAuctionsPerBlock>());
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, 100u32.into(), duration)
+	verify {
+		assert!(Auctions::<T>::contains_key(kitty_id));
+	}
+
+	// The previous best bidder is refunded.
+	bid {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let seller = funded_account::<T>("seller", 0);
+		let kitty_id = create_kitties::<T>(&seller, 1)[0];
+		Kitties::<T>::create_auction(RawOrigin::Signed(seller).into(), kitty_id, 100u32.into(), 10u32.into())?;
+		let bidder = funded_account::<T>("bidder", 0);
+		Kitties::<T>::bid(RawOrigin::Signed(bidder).into(), kitty_id, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id, 200u32.into())
+	verify {
+		assert_last_event::<T>(Event::BidPlaced(caller, kitty_id, 200u32.into()).into());
+	}
+
+	// Every auction has a bid and pays a royalty to a creator other than the seller.
+	on_initialize_auctions {
+		let a in 0 .. T::MaxAuctionsPerBlock::get();
+		let duration: T::BlockNumber = 10u32.into();
+		let end = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+		let mut sold = Vec::new();
+		for i in 0 .. a {
+			let creator = funded_account::<T>("creator", i);
+			let kitty_id = create_kitties::<T>(&creator, 1)[0];
+			let seller = funded_account::<T>("seller", i);
+			Kitties::<T>::transfer(RawOrigin::Signed(creator).into(), seller.clone(), kitty_id)?;
+			Kitties::<T>::create_auction(RawOrigin::Signed(seller).into(), kitty_id, 100u32.into(), duration)?;
+			let bidder = funded_account::<T>("bidder", i);
+			Kitties::<T>::bid(RawOrigin::Signed(bidder.clone()).into(), kitty_id, 100u32.into())?;
+			sold.push((kitty_id, bidder));
+		}
+	}: { Kitties::<T>::on_initialize(end); }
+	verify {
+		for (kitty_id, bidder) in sold {
+			assert_eq!(Kitties::<T>::owner(kitty_id), Some(bidder));
+		}
+	}
+
+	on_initialize_leases {
+		let l in 0 .. T::MaxLeasesPerBlock::get();
+		let duration: T::BlockNumber = 10u32.into();
+		let end = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+		let mut leased = Vec::new();
+		for i in 0 .. l {
+			let owner = funded_account::<T>("owner", i);
+			let kitty_id = create_kitties::<T>(&owner, 1)[0];
+			Kitties::<T>::offer_lease(RawOrigin::Signed(owner).into(), kitty_id, 1u32.into(), duration)?;
+			let user = funded_account::<T>("user", i);
+			Kitties::<T>::take_lease(RawOrigin::Signed(user).into(), kitty_id, duration)?;
+			leased.push(kitty_id);
+		}
+	}: { Kitties::<T>::on_initialize(end); }
+	verify {
+		for kitty_id in leased {
+			assert_eq!(Kitties::<T>::user_of(kitty_id), None);
+		}
+	}
+
+	on_initialize_offers {
+		let o in 0 .. T::MaxOffersPerBlock::get();
+		let expiry = frame_system::Pallet::<T>::block_number().saturating_add(10u32.into());
+		let mut offers = Vec::new();
+		for i in 0 .. o {
+			let owner = funded_account::<T>("owner", i);
+			let kitty_id = create_kitties::<T>(&owner, 1)[0];
+			let bidder = funded_account::<T>("bidder", i);
+			Kitties::<T>::make_offer(RawOrigin::Signed(bidder.clone()).into(), kitty_id, T::MinOfferAmount::get(), expiry)?;
+			offers.push((kitty_id, bidder));
+		}
+	}: { Kitties::<T>::on_initialize(expiry); }
+	verify {
+		for (kitty_id, bidder) in offers {
+			assert!(!Offers::<T>::contains_key(kitty_id, &bidder));
+		}
+	}
+
+	on_initialize_breed_commits {
+		let c in 0 .. T::MaxBreedCommitsPerBlock::get();
+		let expiry = frame_system::Pallet::<T>::block_number()
+			.saturating_add(T::RevealDelay::get())
+			.saturating_add(T::RevealTimeout::get());
+		let salt_hash = T::Hashing::hash_of(&[1u8; 32]);
+		let mut breeders = Vec::new();
+		for i in 0 .. c {
+			let breeder = funded_account::<T>("breeder", i);
+			let kitties = create_kitties::<T>(&breeder, 2);
+			Kitties::<T>::commit_breed(RawOrigin::Signed(breeder.clone()).into(), kitties[0], kitties[1], salt_hash)?;
+			breeders.push(breeder);
+		}
+	}: { Kitties::<T>::on_initialize(expiry); }
+	verify {
+		for breeder in breeders {
+			assert!(!BreedCommits::<T>::contains_key(&breeder));
+		}
+	}
+
+	on_initialize_dutch_listings {
+		let d in 0 .. T::MaxAuctionsPerBlock::get();
+		let duration: T::BlockNumber = 10u32.into();
+		// Listings are delisted the block after their price reaches the floor.
+		let expiry = frame_system::Pallet::<T>::block_number().saturating_add(duration).saturating_add(1u32.into());
+		let mut listed = Vec::new();
+		for i in 0 .. d {
+			let seller = funded_account::<T>("seller", i);
+			let kitty_id = create_kitties::<T>(&seller, 1)[0];
+			Kitties::<T>::to_sell_dutch(RawOrigin::Signed(seller).into(), kitty_id, 100u32.into(), 10u32.into(), duration)?;
+			listed.push(kitty_id);
+		}
+	}: { Kitties::<T>::on_initialize(expiry); }
+	verify {
+		for kitty_id in listed {
+			assert!(!Listings::<T>::contains_key(kitty_id));
+		}
+	}
+}
+
+impl_benchmark_test_suite!(Kitties, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,147 @@
+//! Gene schema of kitty dna.
+//!
+//! The 16 dna bytes are 8 genes of two alleles each:
+//!
+//! | bytes | gene       |
+//! |-------|------------|
+//! | 0-1   | body color |
+//! | 2-3   | pattern    |
+//! | 4-5   | eye shape  |
+//! | 6-7   | rarity     |
+//! | 8-15  | reserved   |
+//!
+//! The lower allele of a gene is dominant and is the one expressed, so a high (rare) value only
+//! shows when both alleles carry it. The expressed value is split into equal ranges, one per
+//! trait variant, in the order the variants are declared.
+
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+
+use crate::Kitty;
+
+pub const DNA_LENGTH: usize = 16;
+pub type Dna = [u8; DNA_LENGTH];
+
+/// A gene mutates when the 6 bit roll is below this, 1 in 64.
+pub const MUTATION_CHANCE: u8 = 1;
+
+const BODY_COLOR_GENE: usize = 0;
+const PATTERN_GENE: usize = 1;
+const EYE_SHAPE_GENE: usize = 2;
+const RARITY_GENE: usize = 3;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum BodyColor {
+	Black,
+	White,
+	Gray,
+	Orange,
+	Cream,
+	Brown,
+	Blue,
+	Lilac,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Pattern {
+	Solid,
+	Tabby,
+	Spotted,
+	Tortoiseshell,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum EyeShape {
+	Round,
+	Almond,
+	Slanted,
+	Sleepy,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum RarityTier {
+	Common,
+	Uncommon,
+	Rare,
+	Legendary,
+}
+
+/// The traits expressed by a kitty's dna.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct KittyTraits {
+	pub body_color: BodyColor,
+	pub pattern: Pattern,
+	pub eye_shape: EyeShape,
+	pub rarity: RarityTier,
+}
+
+/// The expressed (dominant) allele of a gene.
+fn expressed(dna: &Dna, gene: usize) -> u8 {
+	dna[2 * gene].min(dna[2 * gene + 1])
+}
+
+/// Decode the traits of a kitty.
+pub fn traits<AccountId, KittyIndex, BlockNumber>(kitty: &Kitty<AccountId, KittyIndex, BlockNumber>) -> KittyTraits {
+	dna_traits(&kitty.dna)
+}
+
+/// Decode the traits expressed by `dna`.
+pub fn dna_traits(dna: &Dna) -> KittyTraits {
+	let body_color = match expressed(dna, BODY_COLOR_GENE) / 32 {
+		0 => BodyColor::Black,
+		1 => BodyColor::White,
+		2 => BodyColor::Gray,
+		3 => BodyColor::Orange,
+		4 => BodyColor::Cream,
+		5 => BodyColor::Brown,
+		6 => BodyColor::Blue,
+		_ => BodyColor::Lilac,
+	};
+	let pattern = match expressed(dna, PATTERN_GENE) / 64 {
+		0 => Pattern::Solid,
+		1 => Pattern::Tabby,
+		2 => Pattern::Spotted,
+		_ => Pattern::Tortoiseshell,
+	};
+	let eye_shape = match expressed(dna, EYE_SHAPE_GENE) / 64 {
+		0 => EyeShape::Round,
+		1 => EyeShape::Almond,
+		2 => EyeShape::Slanted,
+		_ => EyeShape::Sleepy,
+	};
+	let rarity = match expressed(dna, RARITY_GENE) {
+		0..=159 => RarityTier::Common,
+		160..=223 => RarityTier::Uncommon,
+		224..=247 => RarityTier::Rare,
+		_ => RarityTier::Legendary,
+	};
+	KittyTraits { body_color, pattern, eye_shape, rarity }
+}
+
+/// Mix the dna of two parents.
+///
+/// For every gene the child takes one allele of each parent, chosen by the two low bits of
+/// `random[2 * gene]`. The remaining 6 bits are the mutation roll, a mutated gene takes
+/// `random[2 * gene + 1]` instead of the first parent's allele.
+pub fn inherit(dna_1: &Dna, dna_2: &Dna, random: &[u8; 16]) -> Dna {
+	let mut child = [0u8; DNA_LENGTH];
+	for gene in 0..DNA_LENGTH / 2 {
+		let roll = random[2 * gene];
+		let mut allele_1 = dna_1[2 * gene + (roll & 1) as usize];
+		let allele_2 = dna_2[2 * gene + ((roll >> 1) & 1) as usize];
+		if roll >> 2 < MUTATION_CHANCE {
+			allele_1 = random[2 * gene + 1];
+		}
+		// Keep the dominant allele first.
+		child[2 * gene] = allele_1.min(allele_2);
+		child[2 * gene + 1] = allele_1.max(allele_2);
+	}
+	child
+}
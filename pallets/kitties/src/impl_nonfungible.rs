@@ -0,0 +1,57 @@
+//! Implementations of the `nonfungible` token traits, so kitties can be used by any pallet
+//! expecting the standard NFT interface.
+
+use super::*;
+use frame_support::{
+	dispatch::DispatchResult,
+	ensure,
+	traits::tokens::nonfungible::{Inspect, Mutate, Transfer},
+};
+use sp_std::prelude::*;
+
+/// Attribute key under which the dna of a kitty is exposed.
+pub const DNA_ATTRIBUTE: &[u8] = b"dna";
+
+impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+	type InstanceId = T::KittyIndex;
+
+	fn owner(instance: &Self::InstanceId) -> Option<T::AccountId> {
+		Owner::<T>::get(instance)
+	}
+
+	fn attribute(instance: &Self::InstanceId, key: &[u8]) -> Option<Vec<u8>> {
+		if key == DNA_ATTRIBUTE {
+			Kitties::<T>::get(instance).map(|kitty| kitty.dna.to_vec())
+		} else {
+			None
+		}
+	}
+
+	fn can_transfer(instance: &Self::InstanceId) -> bool {
+		Owner::<T>::get(instance).is_some()
+			&& !Locked::<T>::get(instance)
+			&& !Stakes::<T>::contains_key(instance)
+			&& !Auctions::<T>::contains_key(instance)
+	}
+}
+
+impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(instance: &Self::InstanceId, destination: &T::AccountId) -> DispatchResult {
+		let owner = Owner::<T>::get(instance).ok_or(Error::<T>::InvalidKittyIndex)?;
+		Self::to_transfer(owner, destination.clone(), *instance)
+	}
+}
+
+impl<T: Config> Mutate<T::AccountId> for Pallet<T> {
+	/// Kitty ids are sequential, so only the next free id can be minted. The deposit is reserved
+	/// from `who` as for `create`.
+	fn mint_into(instance: &Self::InstanceId, who: &T::AccountId) -> DispatchResult {
+		ensure!(*instance == Self::next_kitty_id(), Error::<T>::InvalidKittyIndex);
+		Self::do_create(who).map(|_| ())
+	}
+
+	fn burn_from(instance: &Self::InstanceId) -> DispatchResult {
+		let owner = Owner::<T>::get(instance).ok_or(Error::<T>::InvalidKittyIndex)?;
+		Self::do_burn(owner, *instance)
+	}
+}
@@ -11,8 +11,14 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-// #[cfg(feature = "runtime-benchmarks")]
-// mod benchmarking;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod genes;
+pub mod migrations;
+pub mod staking;
+pub mod weights;
+mod impl_nonfungible;
 
 
 use frame_support::{
@@ -42,33 +48,92 @@ const MILLICENTS: u32 = 1_000_000_000;
 
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::{dispatch::DispatchResult, pallet_prelude::*, traits::Randomness};
+	use frame_support::{dispatch::DispatchResult, pallet_prelude::*, traits::Randomness, transactional, PalletId};
 	use frame_system::pallet_prelude::*;
 	use codec::{Encode, Decode};
+	pub use crate::weights::WeightInfo;
+	#[cfg(feature = "std")]
+	use serde::{Deserialize, Serialize};
 	use sp_io::hashing::blake2_128;
 	use sp_std::vec::Vec;
 
 	use sp_runtime::{
-		RuntimeDebug, DispatchError, ArithmeticError,
+		RuntimeDebug, SaturatedConversion, Permill, helpers_128bit::multiply_by_rational, DispatchError, ArithmeticError,
 		traits::{
 			self, CheckedAdd, CheckedSub, AtLeast32Bit, AtLeast32BitUnsigned, BadOrigin, BlockNumberProvider, Bounded,
 			CheckEqual, Dispatchable, Hash, Lookup, LookupError, MaybeDisplay, MaybeMallocSizeOf,
-			MaybeSerializeDeserialize, Member, One, Saturating, SimpleBitOps, StaticLookup, Zero,
+			MaybeSerializeDeserialize, Member, One, Saturating, SimpleBitOps, StaticLookup, Zero, AccountIdConversion,
 		},
 	};
 
 	// use sp_io::misc::{Balance, WithdrawReasons, ExistenceRequirement};
 
-	use sp_std::{cmp, result, mem, fmt::Debug, ops::BitOr};
-	use frame_support::traits::{Currency, LockableCurrency, ReservableCurrency};
-	use frame_benchmarking::frame_support::dispatch::PostDispatchInfo;
-	use frame_benchmarking::frame_support::sp_runtime::DispatchErrorWithPostInfo;
+	use sp_std::{cmp, result, mem, fmt::Debug, ops::BitOr, convert::TryInto};
+	use frame_support::traits::{BalanceStatus, Currency, ExistenceRequirement, LockableCurrency, ReservableCurrency, StorageVersion};
+	use frame_support::storage::bounded_vec::BoundedVec;
 
 	// use sp_runtime::app_crypto::sp_core::blake2_128;
 	// use sp_core::hashing::blake2_128;
 
-	#[derive(Encode, Decode)]
-	pub struct Kitty (pub [u8; 16]);
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct Kitty<AccountId, KittyIndex, BlockNumber> {
+		pub dna: [u8; 16],
+		/// The kitties it was bred from, `None` for a created kitty.
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+		/// 0 for a created kitty, one more than its oldest parent for a bred one.
+		pub generation: u16,
+		/// The kitty can not breed before this block.
+		pub next_breed_block: BlockNumber,
+		/// Who created or bred the kitty and earns royalties on its sales, `None` for kitties
+		/// from before creators were recorded.
+		pub creator: Option<AccountId>,
+		/// The collection the kitty was minted into.
+		pub collection: CollectionId,
+	}
+
+	pub type CollectionId = u32;
+
+	/// Created and bred kitties, and every kitty from before collections, belong to the default
+	/// collection. It has no issuer and no supply cap.
+	pub const DEFAULT_COLLECTION: CollectionId = 0;
+
+	/// A collection of kitties created by an issuer.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct Collection<AccountId, Balance> {
+		/// Who created the collection, is paid for its mints and names it.
+		pub issuer: AccountId,
+		/// The most kitties that can ever be minted into the collection.
+		pub max_supply: u32,
+		/// Kitties minted so far, burned ones included.
+		pub minted: u32,
+		/// Paid to the issuer for every kitty someone else mints.
+		pub mint_price: Balance,
+		/// Reserved from the issuer until the collection is destroyed.
+		pub deposit: Balance,
+	}
+
+	/// How the price of a listing evolves.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub enum PriceMode<Balance, BlockNumber> {
+		/// The listing price never changes.
+		Fixed,
+		/// Dutch auction, the price decays linearly from the listing price at `created_at`
+		/// to `floor_price` at `end`, after which the listing expires.
+		Dutch { floor_price: Balance, end: BlockNumber },
+	}
+
+	/// A kitty put up for sale by its owner.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct Listing<AccountId, Balance, BlockNumber> {
+		/// The owner who listed the kitty.
+		pub seller: AccountId,
+		/// The price asked for the kitty, the start price of a dutch auction.
+		pub price: Balance,
+		/// The block at which the kitty was listed.
+		pub created_at: BlockNumber,
+		/// Whether the price is fixed or decays over time.
+		pub mode: PriceMode<Balance, BlockNumber>,
+	}
 
 	// Define iden
 	// type KittyIndex = u32;
@@ -77,7 +142,151 @@ pub mod pallet {
     // use balance
 	// type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 	// type BalanceOf<T> = <T as pallet_balances::Config>::Balance;
-	type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// Order of the kitties returned by `kitties_for_sale`.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum ListingSort {
+		/// Cheapest first, by the current price.
+		PriceAsc,
+		/// Most expensive first, by the current price.
+		PriceDesc,
+		/// Most recently listed first.
+		Newest,
+		/// Longest listed first.
+		Oldest,
+	}
+
+	/// A kitty for sale as seen by buyers.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct KittySale<AccountId, KittyIndex, Balance, BlockNumber> {
+		pub kitty_id: KittyIndex,
+		pub seller: AccountId,
+		/// The price a buyer pays at the queried block.
+		pub price: Balance,
+		pub listed_at: BlockNumber,
+		/// The block a dutch auction expires, `None` for a fixed price.
+		pub ends_at: Option<BlockNumber>,
+	}
+
+	/// A kitty with its owner and decoded traits, as returned by the runtime api.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct KittyInfo<AccountId, KittyIndex, BlockNumber> {
+		pub kitty_id: KittyIndex,
+		pub owner: AccountId,
+		pub dna: [u8; 16],
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+		pub generation: u16,
+		pub next_breed_block: BlockNumber,
+		pub creator: Option<AccountId>,
+		pub traits: crate::genes::KittyTraits,
+	}
+
+	/// The name and uri of a kitty, as returned by the runtime api.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct KittyMetadataInfo {
+		pub name: Vec<u8>,
+		pub uri: Vec<u8>,
+	}
+
+	/// An English auction of a kitty, the highest bid when it ends wins.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct Auction<AccountId, Balance, BlockNumber> {
+		/// The owner who auctions the kitty.
+		pub seller: AccountId,
+		/// The lowest bid the seller accepts.
+		pub reserve_price: Balance,
+		/// The block at which the auction is settled.
+		pub end: BlockNumber,
+		/// The current highest bidder and bid, the bid is reserved from the bidder.
+		pub best_bid: Option<(AccountId, Balance)>,
+	}
+
+	/// Terms on which the owner lends a kitty.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct LeaseOffer<Balance, BlockNumber> {
+		/// Paid to the owner for every leased block.
+		pub price_per_block: Balance,
+		/// The longest lease the owner accepts.
+		pub max_duration: BlockNumber,
+	}
+
+	/// A running lease, the user breeds the kitty in place of the owner until `end`.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct Lease<AccountId, BlockNumber> {
+		pub user: AccountId,
+		/// The block at which the kitty returns to its owner.
+		pub end: BlockNumber,
+	}
+
+	/// A standing offer on a kitty, the amount is reserved from the bidder until `expiry`.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct Offer<Balance, BlockNumber> {
+		pub amount: Balance,
+		/// The block at which the offer lapses.
+		pub expiry: BlockNumber,
+	}
+
+	/// A kitty offered for breeding, `owner` is paid `fee` for every breeding.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct SireListing<AccountId, Balance> {
+		pub owner: AccountId,
+		pub fee: Balance,
+	}
+
+	/// A committed breeding, the breeder reveals the salt behind `salt_hash` to breed.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct BreedCommit<KittyIndex, Hash, BlockNumber, Balance> {
+		pub parents: (KittyIndex, KittyIndex),
+		pub salt_hash: Hash,
+		/// The first block the salt can be revealed in.
+		pub reveal_from: BlockNumber,
+		/// The block at which the commit lapses and its deposit is refunded.
+		pub expiry: BlockNumber,
+		/// Reserved from the breeder, the deposit of the child.
+		pub deposit: Balance,
+	}
+
+	/// Human readable identity of a kitty.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct KittyMetadata<Balance, NameLimit: Get<u32>, UriLimit: Get<u32>> {
+		pub name: BoundedVec<u8, NameLimit>,
+		/// Where the off-chain description and artwork of the kitty live.
+		pub uri: BoundedVec<u8, UriLimit>,
+		/// The deposit reserved from the owner for the stored bytes.
+		pub deposit: Balance,
+	}
+
+	pub type KittyOf<T> = Kitty<<T as frame_system::Config>::AccountId, <T as Config>::KittyIndex, <T as frame_system::Config>::BlockNumber>;
+
+	pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	pub type ListingOf<T> = Listing<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+	pub type KittySaleOf<T> = KittySale<<T as frame_system::Config>::AccountId, <T as Config>::KittyIndex, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+	pub type KittyInfoOf<T> = KittyInfo<<T as frame_system::Config>::AccountId, <T as Config>::KittyIndex, <T as frame_system::Config>::BlockNumber>;
+
+	pub type AuctionOf<T> = Auction<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+	pub type OfferOf<T> = Offer<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+	pub type SireListingOf<T> = SireListing<<T as frame_system::Config>::AccountId, BalanceOf<T>>;
+
+	pub type LeaseOfferOf<T> = LeaseOffer<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+	pub type LeaseOf<T> = Lease<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+	pub type BreedCommitOf<T> = BreedCommit<<T as Config>::KittyIndex, <T as frame_system::Config>::Hash, <T as frame_system::Config>::BlockNumber, BalanceOf<T>>;
+
+	pub type CollectionOf<T> = Collection<<T as frame_system::Config>::AccountId, BalanceOf<T>>;
+
+	pub type StakeOf<T> = crate::staking::Stake<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+	pub type KittyMetadataOf<T> = KittyMetadata<BalanceOf<T>, <T as Config>::MaxNameLength, <T as Config>::MaxUriLength>;
 
 	const MILLICENTS: u32 = 1_000_000_000;
 
@@ -93,38 +302,340 @@ pub mod pallet {
 		type Currency: ReservableCurrency<Self::AccountId>;
 		// TODO:: 这里不会。
 
+		/// The deposit reserved from the owner of every new kitty, it moves with the kitty and is
+		/// released when the kitty is burned. Existing kitties keep the deposit they were minted
+		/// with.
 		type MaxStakeBalance: Get<BalanceOf<Self>>;
 
+		/// The maximum number of kitties a single account can own.
+		type MaxKittiesOwned: Get<u32>;
+
+		/// The maximum number of auctions that can end in the same block.
+		type MaxAuctionsPerBlock: Get<u32>;
+
+		/// Blocks a generation 0 kitty has to wait between breedings, every generation waits
+		/// this much longer.
+		type BreedCooldown: Get<Self::BlockNumber>;
+
+		/// The maximum number of leases that can end in the same block.
+		type MaxLeasesPerBlock: Get<u32>;
+
+		/// The maximum number of offers that can lapse in the same block.
+		type MaxOffersPerBlock: Get<u32>;
+
+		/// The smallest amount an offer reserves, so the lapsing offers of a block are not
+		/// filled for free.
+		type MinOfferAmount: Get<BalanceOf<Self>>;
+
+		/// The maximum length of a kitty name in bytes.
+		type MaxNameLength: Get<u32>;
+
+		/// The maximum length of a kitty metadata uri in bytes.
+		type MaxUriLength: Get<u32>;
+
+		/// The deposit reserved from the owner for every byte of kitty metadata.
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// The deposit reserved from the issuer of every collection, released when the collection
+		/// is destroyed.
+		type CollectionDeposit: Get<BalanceOf<Self>>;
+
+		/// Whether the metadata of a kitty is cleared, and its deposit refunded, when the kitty
+		/// changes hands. Otherwise the metadata and its deposit move with the kitty.
+		type ClearMetadataOnTransfer: Get<bool>;
+
+		/// The share of every sale paid to the creator of the kitty.
+		type RoyaltyPercent: Get<Permill>;
+
+		/// Blocks between committing to a breeding and revealing its salt, so the block hash
+		/// seeding the child is unknown at commit time.
+		type RevealDelay: Get<Self::BlockNumber>;
+
+		/// Blocks a salt can be revealed in, afterwards the commit lapses. Below
+		/// `BlockHashCount`, the seeding block hash is read at the reveal.
+		type RevealTimeout: Get<Self::BlockNumber>;
+
+		/// The maximum number of breeding commits that can lapse in the same block.
+		type MaxBreedCommitsPerBlock: Get<u32>;
+
+		/// The maximum number of kitties in one `transfer_batch` or `list_batch` call.
+		type MaxBatchSize: Get<u32>;
+
+		/// The origin allowed to create locked kitties, such as event badges.
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The id of the pot account staking rewards are paid from. Nothing mints into it, fund it
+		/// at genesis or with transfers to `Pallet::account_id`.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The reward a staked common kitty earns every block, rarer kitties earn a multiple.
+		type StakingRewardPerBlock: Get<BalanceOf<Self>>;
+
+		/// Information on runtime weights.
+		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	#[pallet::type_value]
+	pub fn DefaultNextKittyId<T: Config>() -> T::KittyIndex {
+		One::one()
+	}
+
+	/// The id given to the next kitty, ids start at 1.
+	#[pallet::storage]
+	#[pallet::getter(fn next_kitty_id)]
+	pub type NextKittyId<T: Config> = StorageValue<_, T::KittyIndex, ValueQuery, DefaultNextKittyId<T>>;
+
+	/// The number of kitties minted and not burned.
 	#[pallet::storage]
 	#[pallet::getter(fn kitties_count)]
-	pub type KittiesCount<T> = StorageValue<_, u32>;
+	pub type LiveKitties<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::type_value]
+	pub fn DefaultNextCollectionId<T: Config>() -> CollectionId {
+		DEFAULT_COLLECTION + 1
+	}
+
+	/// The id given to the next collection, ids start after the default collection.
+	#[pallet::storage]
+	#[pallet::getter(fn next_collection_id)]
+	pub type NextCollectionId<T: Config> = StorageValue<_, CollectionId, ValueQuery, DefaultNextCollectionId<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn collections)]
+	pub type Collections<T: Config> = StorageMap<_, Blake2_128Concat, CollectionId, CollectionOf<T>>;
+
+	/// The kitties of every collection, keyed by collection and kitty. Kitty ids stay global
+	/// rather than `(CollectionId, KittyIndex)` keys on `Kitties`: a kitty is found by its id
+	/// alone in every call, the runtime api and the nonfungible traits, and ids from before
+	/// collections stay valid. This index lists a collection instead.
+	#[pallet::storage]
+	pub type CollectionKitties<T: Config> = StorageDoubleMap<_, Blake2_128Concat, CollectionId, Blake2_128Concat, T::KittyIndex, ()>;
+
+	/// The name and uri given to a collection by its issuer.
+	#[pallet::storage]
+	#[pallet::getter(fn collection_metadata)]
+	pub type CollectionMetadata<T: Config> = StorageMap<_, Blake2_128Concat, CollectionId, KittyMetadataOf<T>>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn kitties)]
-	pub type Kitties<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, Option<Kitty>, ValueQuery>;
+	pub type Kitties<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, KittyOf<T>>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn owner)]
-	pub type Owner<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, Option<T::AccountId>, ValueQuery>;
+	pub type Owner<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
 
+	/// Kitties owned by each account, so they can be listed without scanning `Owner`.
 	#[pallet::storage]
-	#[pallet::getter(fn sell_list)]
-	pub type SellList<T: Config> =  StorageMap<_, Blake2_128Concat, T::AccountId, Vec<(T::KittyIndex, T::Balance)>, ValueQuery>;
+	#[pallet::getter(fn owned_kitties)]
+	pub type OwnedKitties<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<T::KittyIndex, T::MaxKittiesOwned>, ValueQuery>;
+
+	/// The deposit reserved from the owner of each kitty, it moves and is released with the
+	/// kitty. Kitties from before version 2 have none recorded.
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_deposit)]
+	pub type KittyDeposits<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, BalanceOf<T>, ValueQuery>;
+
+	/// The account approved to transfer a kitty on behalf of its owner.
+	#[pallet::storage]
+	#[pallet::getter(fn approvals)]
+	pub type Approvals<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+	/// Operators allowed to transfer all kitties of an owner, keyed by owner and operator.
+	#[pallet::storage]
+	#[pallet::getter(fn operator_approvals)]
+	pub type OperatorApprovals<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
+
+	/// The name and uri given to a kitty by its owner.
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_metadata)]
+	pub type Metadata<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, KittyMetadataOf<T>>;
+
+	/// Kitties currently for sale, keyed by kitty.
+	#[pallet::storage]
+	#[pallet::getter(fn listings)]
+	pub type Listings<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, ListingOf<T>>;
+
+	/// Running auctions, keyed by kitty.
+	#[pallet::storage]
+	#[pallet::getter(fn auctions)]
+	pub type Auctions<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, AuctionOf<T>>;
+
+	/// Auctions to settle, keyed by their end block.
+	#[pallet::storage]
+	#[pallet::getter(fn auction_ends)]
+	pub type AuctionEnds<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, BoundedVec<T::KittyIndex, T::MaxAuctionsPerBlock>, ValueQuery>;
+
+	/// Dutch listings to delist, keyed by the block after their end. They count towards
+	/// `MaxAuctionsPerBlock` like English auctions.
+	#[pallet::storage]
+	#[pallet::getter(fn dutch_listing_ends)]
+	pub type DutchListingEnds<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, BoundedVec<T::KittyIndex, T::MaxAuctionsPerBlock>, ValueQuery>;
+
+	/// Kitties their owner offers to lend.
+	#[pallet::storage]
+	#[pallet::getter(fn lease_offers)]
+	pub type LeaseOffers<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, LeaseOfferOf<T>>;
+
+	/// The user of a leased kitty. The owner can sell it, the user breeds it until the lease ends.
+	#[pallet::storage]
+	#[pallet::getter(fn lease)]
+	pub type User<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, LeaseOf<T>>;
+
+	/// Leases to end, keyed by their end block.
+	#[pallet::storage]
+	#[pallet::getter(fn lease_ends)]
+	pub type LeaseEnds<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, BoundedVec<T::KittyIndex, T::MaxLeasesPerBlock>, ValueQuery>;
+
+	/// Standing offers, keyed by kitty and bidder.
+	#[pallet::storage]
+	#[pallet::getter(fn offers)]
+	pub type Offers<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::KittyIndex, Blake2_128Concat, T::AccountId, OfferOf<T>>;
+
+	/// Offers to release, keyed by their expiry block.
+	#[pallet::storage]
+	#[pallet::getter(fn offer_expiries)]
+	pub type OfferExpiries<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, BoundedVec<(T::KittyIndex, T::AccountId), T::MaxOffersPerBlock>, ValueQuery>;
+
+	/// Kitties offered as sires, keyed by kitty.
+	#[pallet::storage]
+	#[pallet::getter(fn sires)]
+	pub type Sires<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, SireListingOf<T>>;
+
+	/// Kitties that can never change hands, they can only be burned.
+	#[pallet::storage]
+	#[pallet::getter(fn is_locked)]
+	pub type Locked<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, bool, ValueQuery>;
+
+	/// Staked kitties, they can not change hands until unstaked.
+	#[pallet::storage]
+	#[pallet::getter(fn stakes)]
+	pub type Stakes<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, StakeOf<T>>;
+
+	/// Pending commit-reveal breedings, keyed by breeder.
+	#[pallet::storage]
+	#[pallet::getter(fn breed_commits)]
+	pub type BreedCommits<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BreedCommitOf<T>>;
+
+	/// Breeding commits to lapse, keyed by their expiry block.
+	#[pallet::storage]
+	#[pallet::getter(fn breed_commit_expiries)]
+	pub type BreedCommitExpiries<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, BoundedVec<T::AccountId, T::MaxBreedCommitsPerBlock>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Generation 0 kitties to create, as owner and dna. Owners reserve the usual deposit.
+		pub kitties: Vec<(T::AccountId, [u8; 16])>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { kitties: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (owner, dna) in &self.kitties {
+				let deposit = Pallet::<T>::reserve_deposit(owner).expect("genesis kitty owners can pay the deposit");
+				Pallet::<T>::mint_kitty(owner, deposit, Kitty {
+					dna: *dna,
+					parents: None,
+					generation: 0,
+					next_breed_block: Zero::zero(),
+					creator: Some(owner.clone()),
+					collection: DEFAULT_COLLECTION,
+				}).expect("genesis kitty owners own at most MaxKittiesOwned kitties");
+			}
+		}
+	}
 
 	#[pallet::event]
-	#[pallet::metadata(T::AccountId = "AccountId")]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		KittyCreate(T::AccountId, T::KittyIndex),
 		KittyTransfer(T::AccountId, T::AccountId, T::KittyIndex),
-		ToSellList(T::AccountId, T::KittyIndex),
+		/// owner, kitty
+		KittyBurned(T::AccountId, T::KittyIndex),
+		/// owner, approved account (`None` when revoked), kitty
+		Approval(T::AccountId, Option<T::AccountId>, T::KittyIndex),
+		/// owner, operator, approved
+		ApprovalForAll(T::AccountId, T::AccountId, bool),
+		/// kitty, name, uri
+		MetadataSet(T::KittyIndex, Vec<u8>, Vec<u8>),
+		/// kitty
+		MetadataCleared(T::KittyIndex),
+		/// issuer, collection, max supply, mint price
+		CollectionCreated(T::AccountId, CollectionId, u32, BalanceOf<T>),
+		/// collection, name, uri
+		CollectionMetadataSet(CollectionId, Vec<u8>, Vec<u8>),
+		/// collection, the issuer removed its name and uri.
+		CollectionMetadataCleared(CollectionId),
+		/// issuer, collection
+		CollectionDestroyed(T::AccountId, CollectionId),
+		/// seller, kitty, price
+		ToSellList(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// buyer, seller, kitty
 		MakeDeal(T::AccountId, T::AccountId, T::KittyIndex),
+		/// seller, kitty, start price, floor price, end block
+		ToDutchSellList(T::AccountId, T::KittyIndex, BalanceOf<T>, BalanceOf<T>, T::BlockNumber),
+		/// kitty, its dutch auction ended unsold and it is no longer listed.
+		DutchListingExpired(T::KittyIndex),
+		/// seller, kitty
+		SellCanceled(T::AccountId, T::KittyIndex),
+		/// seller, kitty, reserve price, end block
+		AuctionCreated(T::AccountId, T::KittyIndex, BalanceOf<T>, T::BlockNumber),
+		/// bidder, kitty, amount
+		BidPlaced(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// kitty, winner, price
+		AuctionSettled(T::KittyIndex, T::AccountId, BalanceOf<T>),
+		/// kitty, the auction ended without a winner and the kitty stays with the seller.
+		AuctionUnsold(T::KittyIndex),
+		/// owner, kitty, price per block, max duration
+		LeaseOffered(T::AccountId, T::KittyIndex, BalanceOf<T>, T::BlockNumber),
+		/// owner, kitty
+		LeaseOfferCanceled(T::AccountId, T::KittyIndex),
+		/// user, kitty, total price, end
+		LeaseTaken(T::AccountId, T::KittyIndex, BalanceOf<T>, T::BlockNumber),
+		/// kitty, user, the kitty is back with its owner.
+		LeaseEnded(T::KittyIndex, T::AccountId),
+		/// owner, kitty, fee
+		SireListed(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// owner, kitty
+		SireCanceled(T::AccountId, T::KittyIndex),
+		/// bidder, kitty, amount, expiry
+		OfferMade(T::AccountId, T::KittyIndex, BalanceOf<T>, T::BlockNumber),
+		/// bidder, kitty
+		OfferWithdrawn(T::AccountId, T::KittyIndex),
+		/// seller, bidder, kitty, amount
+		OfferAccepted(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// bidder, kitty, the reserved amount is released.
+		OfferExpired(T::AccountId, T::KittyIndex),
+		/// creator, kitty, royalty
+		RoyaltyPaid(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// breeder, sire owner, sire, child, fee
+		SireBred(T::AccountId, T::AccountId, T::KittyIndex, T::KittyIndex, BalanceOf<T>),
+		/// kitty, it can no longer change hands.
+		KittyLocked(T::KittyIndex),
+		/// owner, kitty
+		KittyStaked(T::AccountId, T::KittyIndex),
+		/// owner, kitty, paid reward
+		KittyUnstaked(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		/// breeder, parents, first reveal block
+		BreedCommitted(T::AccountId, T::KittyIndex, T::KittyIndex, T::BlockNumber),
+		/// breeder, the commit was not revealed in time and the deposit is refunded.
+		BreedCommitExpired(T::AccountId),
 	}
 
 	#[pallet::error]
@@ -134,141 +645,931 @@ pub mod pallet {
 		SameParentIndex,
 		InvalidKittyIndex,
 		KittyHasNotSold,
+		TooManyKitties,
+		/// The listed price is higher than the buyer's `max_price`.
+		PriceTooHigh,
+		/// The seller tried to buy their own kitty.
+		BuyOwnKitty,
+		/// The floor price of a dutch auction is above its start price.
+		InvalidPriceRange,
+		/// The dutch auction of the kitty has expired.
+		ListingExpired,
+		/// The kitty is listed for sale.
+		KittyOnSale,
+		/// The kitty is being auctioned.
+		KittyInAuction,
+		/// The auction must last at least one block.
+		InvalidAuctionDuration,
+		/// Too many auctions end in the same block.
+		TooManyAuctions,
+		/// There is no auction for the kitty.
+		AuctionNotExist,
+		/// The auction has already ended.
+		AuctionEnded,
+		/// The bid is below the reserve price or not above the best bid.
+		BidTooLow,
+		/// The seller tried to bid on their own auction.
+		BidOwnAuction,
+		/// The winning bid is no longer reserved from the bidder.
+		BidNotReserved,
+		/// One of the kitties is still in its breeding cooldown.
+		BreedingCooldown,
+		/// Siblings or a parent and its child can not breed.
+		RelatedParents,
+		/// The free balance can not cover the kitty deposit.
+		InsufficientDeposit,
+		/// The new owner has no balance and the deposit is below the existential deposit.
+		DepositBelowExistentialDeposit,
+		/// The caller is neither the owner nor approved for the kitty.
+		NotApproved,
+		/// The caller can not approve themself.
+		ApproveToCaller,
+		/// The kitty name is longer than `MaxNameLength`.
+		NameTooLong,
+		/// The kitty uri is longer than `MaxUriLength`.
+		UriTooLong,
+		/// The owner does not lend the kitty.
+		NoLeaseOffer,
+		/// The kitty is leased.
+		KittyLeased,
+		/// The lease duration is zero or longer than the owner accepts.
+		InvalidLeaseDuration,
+		/// The owner can not lease their own kitty.
+		LeaseOwnKitty,
+		/// Too many leases end in the same block.
+		TooManyLeases,
+		/// The kitty is not offered as a sire.
+		NotSire,
+		/// Breed with your own sire through `bread`.
+		BreedOwnSire,
+		/// The bidder has no offer on the kitty.
+		OfferNotExist,
+		/// The bidder already has an offer on the kitty, withdraw it first.
+		OfferExists,
+		/// The offer expiry is not in the future.
+		InvalidOfferExpiry,
+		/// The owner can not make an offer on their own kitty.
+		OfferOwnKitty,
+		/// The offer amount is below `MinOfferAmount`.
+		OfferTooLow,
+		/// Too many offers lapse in the same block.
+		TooManyOffers,
+		/// The caller already committed to a breeding, reveal it first.
+		BreedCommitExists,
+		/// The caller has no pending breeding commit.
+		NoBreedCommit,
+		/// The reveal delay of the commit has not passed yet.
+		RevealTooEarly,
+		/// The salt does not match the committed hash.
+		InvalidReveal,
+		/// Too many breeding commits lapse in the same block.
+		TooManyBreedCommits,
+		/// The batch holds more than `MaxBatchSize` kitties.
+		BatchTooLarge,
+		/// The kitty is locked to its owner.
+		KittyLocked,
+		/// The kitty is staked, unstake it first.
+		KittyStaked,
+		/// The kitty is not staked.
+		KittyNotStaked,
+		/// Every collection id is taken.
+		CollectionsCountOverflow,
+		/// No collection with this id.
+		CollectionNotFound,
+		/// Only the issuer of the collection can do this.
+		NotCollectionIssuer,
+		/// The collection reached its max supply.
+		CollectionSoldOut,
+		/// A collection needs room for at least one kitty.
+		InvalidMaxSupply,
+		/// The collection still has kitties, burn them first.
+		CollectionNotEmpty,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let ending = AuctionEnds::<T>::take(now);
+			let settled = ending.len() as u32;
+			for kitty_id in ending.iter() {
+				Self::settle_auction(*kitty_id);
+			}
+
+			// Leased kitties return to their owner.
+			let returned = LeaseEnds::<T>::take(now);
+			for kitty_id in returned.iter() {
+				if let Some(lease) = User::<T>::take(kitty_id) {
+					Self::deposit_event(Event::LeaseEnded(*kitty_id, lease.user));
+				}
+			}
+			let returned = returned.len() as u32;
+
+			// Lapsed offers release the funds of their bidder.
+			let lapsed = OfferExpiries::<T>::take(now);
+			for (kitty_id, bidder) in lapsed.iter() {
+				if let Some(offer) = Offers::<T>::take(kitty_id, bidder) {
+					T::Currency::unreserve(bidder, offer.amount);
+					Self::deposit_event(Event::OfferExpired(bidder.clone(), *kitty_id));
+				}
+			}
+			let lapsed = lapsed.len() as u32;
+
+			// Unrevealed breedings refund their deposit.
+			let expired = BreedCommitExpiries::<T>::take(now);
+			for breeder in expired.iter() {
+				if let Some(commit) = BreedCommits::<T>::take(breeder) {
+					T::Currency::unreserve(breeder, commit.deposit);
+					Self::deposit_event(Event::BreedCommitExpired(breeder.clone()));
+				}
+			}
+			let expired = expired.len() as u32;
+
+			// Expired dutch listings are delisted, unless the kitty was listed again since.
+			let delisted = DutchListingEnds::<T>::take(now);
+			for kitty_id in delisted.iter() {
+				let ended = matches!(Listings::<T>::get(kitty_id), Some(Listing { mode: PriceMode::Dutch { end, .. }, .. }) if end < now);
+				if ended {
+					Listings::<T>::remove(kitty_id);
+					Self::deposit_event(Event::DutchListingExpired(*kitty_id));
+				}
+			}
+			let delisted = delisted.len() as u32;
+
+			// Each benchmark also takes the other, empty, queues: the sum overestimates a little.
+			T::WeightInfo::on_initialize_auctions(settled)
+				.saturating_add(T::WeightInfo::on_initialize_leases(returned))
+				.saturating_add(T::WeightInfo::on_initialize_offers(lapsed))
+				.saturating_add(T::WeightInfo::on_initialize_breed_commits(expired))
+				.saturating_add(T::WeightInfo::on_initialize_dutch_listings(delisted))
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let version = StorageVersion::get::<Pallet<T>>();
+			if version < STORAGE_VERSION {
+				crate::migrations::migrate::<T>(version)
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		fn integrity_test() {
+			// The hash seeding a breeding must still be stored until its commit lapses.
+			assert!(
+				T::RevealTimeout::get() < T::BlockHashCount::get(),
+				"RevealTimeout must be below BlockHashCount",
+			);
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			crate::migrations::v3::pre_upgrade::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			crate::migrations::v3::post_upgrade::<T>()?;
+			crate::migrations::v4::post_upgrade::<T>()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::create())]
+		#[transactional]
 		pub fn create(origin: OriginFor<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::do_create(&who)?;
+			Ok(())
+		}
 
-			// stake some balance
-			T::Currency::reserve(&who, T::MaxStakeBalance::get());
+		/// Create a kitty for `owner`, who pays its deposit. A `locked` kitty can never change
+		/// hands.
+		#[pallet::weight(T::WeightInfo::force_create())]
+		#[transactional]
+		pub fn force_create(origin: OriginFor<T>, owner: T::AccountId, locked: bool) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let kitty_id = Self::do_create(&owner)?;
+			if locked {
+				Self::do_lock(kitty_id);
+			}
+			Ok(())
+		}
 
-			let mut kitty_count = Self::get_count_of_kitty();
-			ensure!(kitty_count != u32::max_value(), Error::<T>::KittiesCountOverflow) ;
+		/// Lock a kitty to its owner for good. It is taken off sale and its approval is revoked.
+		#[pallet::weight(T::WeightInfo::lock())]
+		pub fn lock(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
 
-			// Add count
-			kitty_count += 1;
-			// Add kitty id
-			let kitty_id : T::KittyIndex = kitty_count.into() ;
-			// // 获取 dna
-			let dna = Self::random_value(&who);
-			Kitties::<T>::insert(kitty_id, Some(Kitty(dna)));
-			Owner::<T>::insert(kitty_id, Some(who.clone()));
-			// Set to kittie count.
-			KittiesCount::<T>::put(kitty_count);
-			// Emit event
-			Self::deposit_event(Event::KittyCreate(who, kitty_id));
-			// let a:Vec<u8> = Vec::new();
+			Self::remove_listing(kitty_id);
+			Approvals::<T>::remove(kitty_id);
+			Self::do_lock(kitty_id);
+			Ok(())
+		}
+
+		/// Stake a kitty to earn rewards every block. Its listing and approval are cleared and it
+		/// can not change hands until unstaked.
+		#[pallet::weight(T::WeightInfo::stake_kitty())]
+		pub fn stake_kitty(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			ensure!(!Stakes::<T>::contains_key(kitty_id), Error::<T>::KittyStaked);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+
+			Self::remove_listing(kitty_id);
+			Approvals::<T>::remove(kitty_id);
+			Stakes::<T>::insert(kitty_id, crate::staking::Stake {
+				owner: who.clone(),
+				since: frame_system::Pallet::<T>::block_number(),
+			});
+			Self::deposit_event(Event::KittyStaked(who, kitty_id));
+			Ok(())
+		}
+
+		/// Unstake a kitty and claim its rewards. If the pot can not cover them, its whole
+		/// balance is paid.
+		#[pallet::weight(T::WeightInfo::unstake_kitty())]
+		pub fn unstake_kitty(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let stake = Self::stakes(kitty_id).ok_or(Error::<T>::KittyNotStaked)?;
+			ensure!(stake.owner == who, Error::<T>::NotOwner);
+
+			let pot = Self::account_id();
+			let reward = Self::pending_reward(kitty_id).unwrap_or_else(Zero::zero).min(T::Currency::free_balance(&pot));
+			T::Currency::transfer(&pot, &who, reward, ExistenceRequirement::AllowDeath)?;
+			Stakes::<T>::remove(kitty_id);
+			Self::deposit_event(Event::KittyUnstaked(who, kitty_id, reward));
 			Ok(())
 		}
 
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::transfer())]
 		pub fn transfer(origin: OriginFor<T>, new_owner: T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			Self::to_transfer(who.clone(), new_owner.clone(), kitty_id.clone())
 		}
 
-		#[pallet::weight(0)]
-		pub fn bread(origin: OriginFor<T>, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> DispatchResult {
+		/// Transfer up to `MaxBatchSize` kitties to `new_owner`, all of them or none.
+		#[pallet::weight(T::WeightInfo::transfer_batch(kitty_ids.len() as u32))]
+		#[transactional]
+		pub fn transfer_batch(origin: OriginFor<T>, new_owner: T::AccountId, kitty_ids: Vec<T::KittyIndex>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			ensure!(kitty_id_1 != kitty_id_2, Error::<T>::SameParentIndex);
-			let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyIndex)?;
-			let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(kitty_ids.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+			for kitty_id in kitty_ids {
+				Self::to_transfer(who.clone(), new_owner.clone(), kitty_id)?;
+			}
+			Ok(())
+		}
 
+		/// Approve `spender` to transfer the kitty, `None` revokes the approval. Callable by the
+		/// owner or one of its operators, the approval is cleared when the kitty is transferred.
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(origin: OriginFor<T>, kitty_id: T::KittyIndex, spender: Option<T::AccountId>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let owner = Self::owner(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(who == owner || Self::operator_approvals(&owner, &who), Error::<T>::NotApproved);
+			ensure!(spender.as_ref() != Some(&owner), Error::<T>::ApproveToCaller);
+			ensure!(spender.is_none() || !Self::is_locked(kitty_id), Error::<T>::KittyLocked);
 
-			let mut kitty_count = Self::get_count_of_kitty();
-			ensure!(kitty_count != u32::max_value(), Error::<T>::KittiesCountOverflow) ;
+			match &spender {
+				Some(spender) => Approvals::<T>::insert(kitty_id, spender),
+				None => Approvals::<T>::remove(kitty_id),
+			}
+			Self::deposit_event(Event::Approval(owner, spender, kitty_id));
+			Ok(())
+		}
 
-			let dna_1 = kitty1.0;
-			let dna_2 = kitty2.0;
+		/// Allow or disallow `operator` to transfer and approve all kitties of the caller.
+		#[pallet::weight(T::WeightInfo::set_approval_for_all())]
+		pub fn set_approval_for_all(origin: OriginFor<T>, operator: T::AccountId, approved: bool) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(who != operator, Error::<T>::ApproveToCaller);
 
-			let selector = Self::random_value(&who);
-			let mut new_dna = [0u8; 16];
+			if approved {
+				OperatorApprovals::<T>::insert(&who, &operator, true);
+			} else {
+				OperatorApprovals::<T>::remove(&who, &operator);
+			}
+			Self::deposit_event(Event::ApprovalForAll(who, operator, approved));
+			Ok(())
+		}
 
-			for i in 0..dna_1.len() {
-				new_dna[i] = (selector[i] & dna_1[i]) | (!selector[i] & dna_2[i]);
+		/// Transfer a kitty of `from` as its owner, its approved account or an operator of `from`.
+		#[pallet::weight(T::WeightInfo::transfer_from())]
+		pub fn transfer_from(origin: OriginFor<T>, from: T::AccountId, to: T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(from.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			ensure!(Self::is_approved_or_owner(&who, kitty_id), Error::<T>::NotApproved);
+			Self::to_transfer(from, to, kitty_id)
+		}
+
+		/// Name a kitty and point to its off-chain description. The owner reserves
+		/// `MetadataDepositPerByte` for every stored byte, empty values clear the metadata.
+		#[pallet::weight(T::WeightInfo::set_metadata(name.len() as u32, uri.len() as u32))]
+		#[transactional]
+		pub fn set_metadata(origin: OriginFor<T>, kitty_id: T::KittyIndex, name: Vec<u8>, uri: Vec<u8>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+
+			if name.is_empty() && uri.is_empty() {
+				Self::clear_metadata(&who, kitty_id);
+				return Ok(());
 			}
 
-			// Sum kitty_count
-			kitty_count += 1;
-			// Add kitty id
-			let kitty_id : T::KittyIndex = kitty_count.into() ;
-			Kitties::<T>::insert(kitty_id, Some(Kitty(new_dna)));
-			Owner::<T>::insert(kitty_id, Some(who.clone()));
-			KittiesCount::<T>::put(kitty_count );
-			Self::deposit_event(Event::KittyCreate(who, kitty_id));
+			let metadata = Self::reserve_metadata(&who, Metadata::<T>::get(kitty_id), &name, &uri)?;
+			Metadata::<T>::insert(kitty_id, metadata);
+			Self::deposit_event(Event::MetadataSet(kitty_id, name, uri));
+			Ok(())
+		}
+
+		/// Create a collection issued by the caller, who reserves `CollectionDeposit`. At most
+		/// `max_supply` kitties can be minted into it, anyone but the issuer pays `mint_price` to
+		/// the issuer for each.
+		#[pallet::weight(T::WeightInfo::create_collection())]
+		pub fn create_collection(origin: OriginFor<T>, max_supply: u32, mint_price: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(max_supply > 0, Error::<T>::InvalidMaxSupply);
+
+			let collection_id = Self::next_collection_id();
+			let next_collection_id = collection_id.checked_add(1).ok_or(Error::<T>::CollectionsCountOverflow)?;
+			let deposit = T::CollectionDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+			Collections::<T>::insert(collection_id, Collection { issuer: who.clone(), max_supply, minted: 0, mint_price, deposit });
+			NextCollectionId::<T>::put(next_collection_id);
+			Self::deposit_event(Event::CollectionCreated(who, collection_id, max_supply, mint_price));
+			Ok(())
+		}
+
+		/// Mint a kitty with random dna into a collection, reserving the usual deposit.
+		#[pallet::weight(T::WeightInfo::mint())]
+		#[transactional]
+		pub fn mint(origin: OriginFor<T>, collection_id: CollectionId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_mint(&who, collection_id)?;
 			Ok(())
 		}
 
-		#[pallet::weight(0)]
-		pub fn to_sell(origin: OriginFor<T>, kitty_id: T::KittyIndex, balance: T::Balance ) -> DispatchResult {
+		/// Name a collection and point to its off-chain description. Works like `set_metadata`,
+		/// the issuer reserves the deposit.
+		#[pallet::weight(T::WeightInfo::set_collection_metadata(name.len() as u32, uri.len() as u32))]
+		#[transactional]
+		pub fn set_collection_metadata(origin: OriginFor<T>, collection_id: CollectionId, name: Vec<u8>, uri: Vec<u8>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			let collection = Self::collections(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(collection.issuer == who, Error::<T>::NotCollectionIssuer);
 
-			let mut sell_list = SellList::<T>::get(who.clone()) ;
-			for (tmp_index, (tmp_kitty_id, _)) in sell_list.clone().iter().enumerate() {
-				if tmp_kitty_id == &kitty_id {
-					// Del old.
-					sell_list.remove(tmp_index);
+			if name.is_empty() && uri.is_empty() {
+				if let Some(metadata) = CollectionMetadata::<T>::take(collection_id) {
+					T::Currency::unreserve(&who, metadata.deposit);
+					Self::deposit_event(Event::CollectionMetadataCleared(collection_id));
 				}
+				return Ok(());
+			}
+
+			let metadata = Self::reserve_metadata(&who, CollectionMetadata::<T>::get(collection_id), &name, &uri)?;
+			CollectionMetadata::<T>::insert(collection_id, metadata);
+			Self::deposit_event(Event::CollectionMetadataSet(collection_id, name, uri));
+			Ok(())
+		}
+
+		/// Destroy a collection without kitties left, releasing its deposit and the deposit of
+		/// its metadata. Its id is not given out again.
+		#[pallet::weight(T::WeightInfo::destroy_collection())]
+		pub fn destroy_collection(origin: OriginFor<T>, collection_id: CollectionId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let collection = Self::collections(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(collection.issuer == who, Error::<T>::NotCollectionIssuer);
+			ensure!(CollectionKitties::<T>::iter_key_prefix(collection_id).next().is_none(), Error::<T>::CollectionNotEmpty);
+
+			Collections::<T>::remove(collection_id);
+			if let Some(metadata) = CollectionMetadata::<T>::take(collection_id) {
+				T::Currency::unreserve(&who, metadata.deposit);
+			}
+			T::Currency::unreserve(&who, collection.deposit);
+			Self::deposit_event(Event::CollectionDestroyed(who, collection_id));
+			Ok(())
+		}
+
+		/// Delete a kitty and release its deposit.
+		#[pallet::weight(T::WeightInfo::burn())]
+		pub fn burn(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyIndex);
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			Self::do_burn(who, kitty_id)
+		}
+
+		#[pallet::weight(T::WeightInfo::bread())]
+		#[transactional]
+		pub fn bread(origin: OriginFor<T>, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let (kitty1, kitty2) = Self::breeding_parents(&who, kitty_id_1, kitty_id_2)?;
+			Self::do_breed(&who, (kitty_id_1, kitty1), (kitty_id_2, kitty2))?;
+			Ok(())
+		}
+
+		/// Commit to breeding two kitties with a salt hashed by `T::Hashing`, reserving the
+		/// kitty deposit. The salt is revealed with `reveal_breed` after `RevealDelay` blocks, so
+		/// the child dna depends on a block hash nobody knows at commit time.
+		#[pallet::weight(T::WeightInfo::commit_breed())]
+		#[transactional]
+		pub fn commit_breed(origin: OriginFor<T>, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex, salt_hash: T::Hash) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!BreedCommits::<T>::contains_key(&who), Error::<T>::BreedCommitExists);
+			let (kitty1, kitty2) = Self::breeding_parents(&who, kitty_id_1, kitty_id_2)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			Self::ensure_can_breed(kitty_id_1, &kitty1, kitty_id_2, &kitty2, now)?;
+
+			let reveal_from = now.saturating_add(T::RevealDelay::get());
+			let expiry = reveal_from.saturating_add(T::RevealTimeout::get());
+			BreedCommitExpiries::<T>::try_mutate(expiry, |lapsing| {
+				lapsing.try_push(who.clone()).map_err(|_| Error::<T>::TooManyBreedCommits)
+			})?;
+			let deposit = Self::reserve_deposit(&who)?;
+			BreedCommits::<T>::insert(&who, BreedCommit { parents: (kitty_id_1, kitty_id_2), salt_hash, reveal_from, expiry, deposit });
+			Self::deposit_event(Event::BreedCommitted(who, kitty_id_1, kitty_id_2, reveal_from));
+			Ok(())
+		}
+
+		/// Breed the committed parents, mixing `salt` with the hash of the block before
+		/// `reveal_from`. The child is the same whichever block it is revealed in. The deposit
+		/// reserved by `commit_breed` becomes the deposit of the child.
+		#[pallet::weight(T::WeightInfo::reveal_breed())]
+		#[transactional]
+		pub fn reveal_breed(origin: OriginFor<T>, salt: [u8; 32]) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let commit = Self::breed_commits(&who).ok_or(Error::<T>::NoBreedCommit)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(now >= commit.reveal_from, Error::<T>::RevealTooEarly);
+			ensure!(T::Hashing::hash_of(&salt) == commit.salt_hash, Error::<T>::InvalidReveal);
+
+			// The parents may have changed hands or bred since the commit.
+			let (kitty_id_1, kitty_id_2) = commit.parents;
+			let (kitty1, kitty2) = Self::breeding_parents(&who, kitty_id_1, kitty_id_2)?;
+			Self::ensure_can_breed(kitty_id_1, &kitty1, kitty_id_2, &kitty2, now)?;
+
+			BreedCommits::<T>::remove(&who);
+			BreedCommitExpiries::<T>::mutate(commit.expiry, |lapsing| lapsing.retain(|breeder| breeder != &who));
+			let seed_block = commit.reveal_from.saturating_sub(One::one());
+			let seed = (frame_system::Pallet::<T>::block_hash(seed_block), &who, &salt).using_encoded(blake2_128);
+			Self::mint_child(&who, commit.deposit, (kitty_id_1, kitty1), (kitty_id_2, kitty2), &seed)?;
+			Ok(())
+		}
+
+		/// Offer a kitty as a sire, anyone can breed with it for `fee`.
+		#[pallet::weight(T::WeightInfo::list_sire())]
+		pub fn list_sire(origin: OriginFor<T>, kitty_id: T::KittyIndex, fee: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::user_of(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+
+			Sires::<T>::insert(kitty_id, SireListing { owner: who.clone(), fee });
+			Self::deposit_event(Event::SireListed(who, kitty_id, fee));
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::cancel_sire())]
+		pub fn cancel_sire(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let listing = Self::sires(kitty_id).ok_or(Error::<T>::NotSire)?;
+			ensure!(listing.owner == who, Error::<T>::NotOwner);
+
+			Sires::<T>::remove(kitty_id);
+			Self::deposit_event(Event::SireCanceled(who, kitty_id));
+			Ok(())
+		}
+
+		/// Breed `my_kitty` with a listed sire, paying its fee (at most `max_fee`) to the sire
+		/// owner. The child belongs to the caller.
+		#[pallet::weight(T::WeightInfo::breed_with_sire())]
+		#[transactional]
+		pub fn breed_with_sire(origin: OriginFor<T>, my_kitty: T::KittyIndex, sire_kitty: T::KittyIndex, max_fee: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(my_kitty != sire_kitty, Error::<T>::SameParentIndex);
+			let kitty = Self::kitties(my_kitty).ok_or(Error::<T>::InvalidKittyIndex)?;
+			let sire = Self::kitties(sire_kitty).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(Self::user_of(my_kitty) == Some(who.clone()), Error::<T>::NotOwner);
+			let listing = Self::sires(sire_kitty).ok_or(Error::<T>::NotSire)?;
+			// The sire went on lease after it was listed.
+			ensure!(Self::user_of(sire_kitty) == Some(listing.owner.clone()), Error::<T>::NotSire);
+			ensure!(listing.owner != who, Error::<T>::BreedOwnSire);
+			ensure!(listing.fee <= max_fee, Error::<T>::PriceTooHigh);
+
+			T::Currency::transfer(&who, &listing.owner, listing.fee, ExistenceRequirement::KeepAlive)?;
+			let child = Self::do_breed(&who, (my_kitty, kitty), (sire_kitty, sire))?;
+			Self::deposit_event(Event::SireBred(who, listing.owner, sire_kitty, child, listing.fee));
+			Ok(())
+		}
+
+		/// Offer to lend a kitty for up to `max_duration` blocks at `price_per_block`.
+		#[pallet::weight(T::WeightInfo::offer_lease())]
+		pub fn offer_lease(origin: OriginFor<T>, kitty_id: T::KittyIndex, price_per_block: BalanceOf<T>, max_duration: T::BlockNumber) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			ensure!(!max_duration.is_zero(), Error::<T>::InvalidLeaseDuration);
+
+			LeaseOffers::<T>::insert(kitty_id, LeaseOffer { price_per_block, max_duration });
+			Self::deposit_event(Event::LeaseOffered(who, kitty_id, price_per_block, max_duration));
+			Ok(())
+		}
+
+		/// Stop lending a kitty, a running lease still ends as agreed.
+		#[pallet::weight(T::WeightInfo::cancel_lease_offer())]
+		pub fn cancel_lease_offer(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			ensure!(LeaseOffers::<T>::contains_key(kitty_id), Error::<T>::NoLeaseOffer);
+
+			LeaseOffers::<T>::remove(kitty_id);
+			Self::deposit_event(Event::LeaseOfferCanceled(who, kitty_id));
+			Ok(())
+		}
+
+		/// Lease a kitty for `duration` blocks, paying the whole price to its owner upfront.
+		#[pallet::weight(T::WeightInfo::take_lease())]
+		#[transactional]
+		pub fn take_lease(origin: OriginFor<T>, kitty_id: T::KittyIndex, duration: T::BlockNumber) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let offer = Self::lease_offers(kitty_id).ok_or(Error::<T>::NoLeaseOffer)?;
+			let owner = Self::owner(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(who != owner, Error::<T>::LeaseOwnKitty);
+			ensure!(!User::<T>::contains_key(kitty_id), Error::<T>::KittyLeased);
+			ensure!(!duration.is_zero() && duration <= offer.max_duration, Error::<T>::InvalidLeaseDuration);
+
+			let blocks: u32 = duration.saturated_into();
+			let price = offer.price_per_block.saturating_mul(blocks.into());
+			T::Currency::transfer(&who, &owner, price, ExistenceRequirement::KeepAlive)?;
+
+			let end = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+			LeaseEnds::<T>::try_mutate(end, |ending| {
+				ending.try_push(kitty_id).map_err(|_| Error::<T>::TooManyLeases)
+			})?;
+			User::<T>::insert(kitty_id, Lease { user: who.clone(), end });
+			Self::deposit_event(Event::LeaseTaken(who, kitty_id, price, end));
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::to_sell())]
+		pub fn to_sell(origin: OriginFor<T>, kitty_id: T::KittyIndex, price: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_sell(who, kitty_id, price)
+		}
+
+		/// List up to `MaxBatchSize` kitties at fixed prices, all of them or none.
+		#[pallet::weight(T::WeightInfo::list_batch(listings.len() as u32))]
+		#[transactional]
+		pub fn list_batch(origin: OriginFor<T>, listings: Vec<(T::KittyIndex, BalanceOf<T>)>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(listings.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+			for (kitty_id, price) in listings {
+				Self::do_sell(who.clone(), kitty_id, price)?;
 			}
-			// update or set new price.
-			sell_list.push((kitty_id.clone(),balance));
-			SellList::<T>::insert(who.clone(), sell_list);
-			//
-			Self::deposit_event(Event::ToSellList(who, kitty_id));
 			Ok(())
 		}
 
-		#[pallet::weight(0)]
-		pub fn to_buy(origin: OriginFor<T>, dest: <T::Lookup as StaticLookup>::Source, kitty_id: T::KittyIndex ) -> DispatchResult {
-			let who = ensure_signed(origin.clone())?;
+		/// List a kitty whose price drops from `start_price` to `floor_price` over `duration` blocks.
+		#[pallet::weight(T::WeightInfo::to_sell_dutch())]
+		#[transactional]
+		pub fn to_sell_dutch(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			start_price: BalanceOf<T>,
+			floor_price: BalanceOf<T>,
+			duration: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+			ensure!(!Stakes::<T>::contains_key(kitty_id), Error::<T>::KittyStaked);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(floor_price <= start_price, Error::<T>::InvalidPriceRange);
+			ensure!(!duration.is_zero(), Error::<T>::InvalidAuctionDuration);
 
-			let owner_id = <T::Lookup as StaticLookup>::lookup(dest.clone())?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let end = now.saturating_add(duration);
+			Self::remove_listing(kitty_id);
+			DutchListingEnds::<T>::try_mutate(end.saturating_add(One::one()), |ending| {
+				ending.try_push(kitty_id).map_err(|_| Error::<T>::TooManyAuctions)
+			})?;
+			Listings::<T>::insert(kitty_id, Listing {
+				seller: who.clone(),
+				price: start_price,
+				created_at: now,
+				mode: PriceMode::Dutch { floor_price, end },
+			});
+			Self::deposit_event(Event::ToDutchSellList(who, kitty_id, start_price, floor_price, end));
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::cancel_sell())]
+		pub fn cancel_sell(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let listing = Self::listings(kitty_id).ok_or(Error::<T>::KittyHasNotSold)?;
+			ensure!(listing.seller == who, Error::<T>::NotOwner);
+
+			Self::remove_listing(kitty_id);
+			Self::deposit_event(Event::SellCanceled(who, kitty_id));
+			Ok(())
+		}
+
+		/// Buy a listed kitty. Payment and ownership change happen together or not at all.
+		#[pallet::weight(T::WeightInfo::to_buy())]
+		#[transactional]
+		pub fn to_buy(origin: OriginFor<T>, kitty_id: T::KittyIndex, max_price: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
 			// 1. first check whether kitty is being sold, if not display an Error.
-			let mut sell_list = SellList::<T>::get(owner_id.clone()) ;
-			let mut kitty_is_sold = false;
-			for (_, (tmp_kitty_id, tmp_balance)) in sell_list.clone().iter().enumerate() {
-				if tmp_kitty_id == &kitty_id {
-					kitty_is_sold = true;
-					// <pallet_balances::Pallet<T>>::reserved_balance()
-					match <pallet_balances::Pallet<T>>::transfer(origin.clone(), dest.clone(), tmp_balance.clone())  {
-						Ok(_) => {
-							// TODO:: It should be judged that if the transmission fails, you need to refund the money.
-							// Un stake.
-							T::Currency::unreserve(&owner_id, T::MaxStakeBalance::get());
-							let result = Self::to_transfer(owner_id.clone(), who.clone(), kitty_id.clone());
-							Self::deposit_event(Event::MakeDeal(who, owner_id.clone(), kitty_id));
-
-							// Clear sell list.
-							let mut sell_list = SellList::<T>::get(owner_id.clone()) ;
-							for (tmp_index, (tmp_kitty_id, _)) in sell_list.clone().iter().enumerate() {
-								if tmp_kitty_id == &kitty_id {
-									// Del old.
-									sell_list.remove(tmp_index);
-								}
-							}
-							SellList::<T>::insert(owner_id.clone(), sell_list);
-
-							return result;
-						}
-						Err(e) => {
-							return Err(e.error) ;
-						}
-					}
-				}
+			let listing = Self::listings(kitty_id).ok_or(Error::<T>::KittyHasNotSold)?;
+			ensure!(listing.seller != who, Error::<T>::BuyOwnKitty);
+			let price = Self::listing_price(&listing, frame_system::Pallet::<T>::block_number())
+				.ok_or(Error::<T>::ListingExpired)?;
+			ensure!(price <= max_price, Error::<T>::PriceTooHigh);
+
+			// 2. pay the creator royalty and the seller, then hand over the kitty with its deposit,
+			// the listing is removed by `to_transfer`.
+			let royalty = Self::pay_royalty(&who, &listing.seller, kitty_id, price)?;
+			T::Currency::transfer(&who, &listing.seller, price.saturating_sub(royalty), ExistenceRequirement::KeepAlive)?;
+			Self::to_transfer(listing.seller.clone(), who.clone(), kitty_id)?;
+
+			Self::deposit_event(Event::MakeDeal(who, listing.seller, kitty_id));
+			Ok(())
+		}
+
+		/// Offer `amount` for a kitty, listed or not, until the `expiry` block. The amount is
+		/// reserved until the offer is accepted, withdrawn or lapses.
+		#[pallet::weight(T::WeightInfo::make_offer())]
+		#[transactional]
+		pub fn make_offer(origin: OriginFor<T>, kitty_id: T::KittyIndex, amount: BalanceOf<T>, expiry: T::BlockNumber) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let owner = Self::owner(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(owner != who, Error::<T>::OfferOwnKitty);
+			ensure!(amount >= T::MinOfferAmount::get(), Error::<T>::OfferTooLow);
+			ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+			ensure!(!Offers::<T>::contains_key(kitty_id, &who), Error::<T>::OfferExists);
+			ensure!(expiry > frame_system::Pallet::<T>::block_number(), Error::<T>::InvalidOfferExpiry);
+
+			OfferExpiries::<T>::try_mutate(expiry, |lapsing| {
+				lapsing.try_push((kitty_id, who.clone())).map_err(|_| Error::<T>::TooManyOffers)
+			})?;
+			T::Currency::reserve(&who, amount)?;
+			Offers::<T>::insert(kitty_id, &who, Offer { amount, expiry });
+			Self::deposit_event(Event::OfferMade(who, kitty_id, amount, expiry));
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::withdraw_offer())]
+		pub fn withdraw_offer(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let offer = Self::take_offer(kitty_id, &who)?;
+
+			T::Currency::unreserve(&who, offer.amount);
+			Self::deposit_event(Event::OfferWithdrawn(who, kitty_id));
+			Ok(())
+		}
+
+		/// Sell the kitty to `bidder` for their offer, royalties are paid as for `to_buy`.
+		#[pallet::weight(T::WeightInfo::accept_offer())]
+		#[transactional]
+		pub fn accept_offer(origin: OriginFor<T>, kitty_id: T::KittyIndex, bidder: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			let offer = Self::take_offer(kitty_id, &bidder)?;
+
+			T::Currency::unreserve(&bidder, offer.amount);
+			let royalty = Self::pay_royalty(&bidder, &who, kitty_id, offer.amount)?;
+			T::Currency::transfer(&bidder, &who, offer.amount.saturating_sub(royalty), ExistenceRequirement::KeepAlive)?;
+			Self::to_transfer(who.clone(), bidder.clone(), kitty_id)?;
+
+			Self::deposit_event(Event::OfferAccepted(who, bidder, kitty_id, offer.amount));
+			Ok(())
+		}
+
+		/// Auction a kitty for `duration` blocks, it is settled in `on_initialize` of the end block.
+		#[pallet::weight(T::WeightInfo::create_auction())]
+		pub fn create_auction(origin: OriginFor<T>, kitty_id: T::KittyIndex, reserve_price: BalanceOf<T>, duration: T::BlockNumber) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+			ensure!(!Stakes::<T>::contains_key(kitty_id), Error::<T>::KittyStaked);
+			ensure!(!Listings::<T>::contains_key(kitty_id), Error::<T>::KittyOnSale);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!duration.is_zero(), Error::<T>::InvalidAuctionDuration);
+
+			let end = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+			AuctionEnds::<T>::try_mutate(end, |ending| {
+				ending.try_push(kitty_id).map_err(|_| Error::<T>::TooManyAuctions)
+			})?;
+			Auctions::<T>::insert(kitty_id, Auction {
+				seller: who.clone(),
+				reserve_price,
+				end,
+				best_bid: None,
+			});
+			Self::deposit_event(Event::AuctionCreated(who, kitty_id, reserve_price, end));
+			Ok(())
+		}
+
+		/// Bid on an auction, the bid is reserved and the previous best bidder is refunded.
+		#[pallet::weight(T::WeightInfo::bid())]
+		pub fn bid(origin: OriginFor<T>, kitty_id: T::KittyIndex, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut auction = Self::auctions(kitty_id).ok_or(Error::<T>::AuctionNotExist)?;
+			ensure!(frame_system::Pallet::<T>::block_number() < auction.end, Error::<T>::AuctionEnded);
+			ensure!(auction.seller != who, Error::<T>::BidOwnAuction);
+			ensure!(amount >= auction.reserve_price, Error::<T>::BidTooLow);
+			if let Some((_, best)) = &auction.best_bid {
+				ensure!(amount > *best, Error::<T>::BidTooLow);
 			}
 
-			// not exists on the list
-			if false == kitty_is_sold {return Err(Error::<T>::KittyHasNotSold)?}
+			T::Currency::reserve(&who, amount)?;
+			if let Some((bidder, best)) = auction.best_bid.take() {
+				T::Currency::unreserve(&bidder, best);
+			}
+			auction.best_bid = Some((who.clone(), amount));
+			Auctions::<T>::insert(kitty_id, auction);
 
+			Self::deposit_event(Event::BidPlaced(who, kitty_id, amount));
 			Ok(())
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Breed two kitties into a new one owned by `who`, who pays its deposit. Both parents
+		/// have to be unrelated and rested, and rest again afterwards.
+		fn do_breed(
+			who: &T::AccountId,
+			(kitty_id_1, kitty1): (T::KittyIndex, KittyOf<T>),
+			(kitty_id_2, kitty2): (T::KittyIndex, KittyOf<T>),
+		) -> Result<T::KittyIndex, DispatchError> {
+			let now = frame_system::Pallet::<T>::block_number();
+			Self::ensure_can_breed(kitty_id_1, &kitty1, kitty_id_2, &kitty2, now)?;
+			let deposit = Self::reserve_deposit(who)?;
+			Self::mint_child(who, deposit, (kitty_id_1, kitty1), (kitty_id_2, kitty2), &Self::random_value(who))
+		}
+
+		/// The two kitties `who` wants to breed. The user of a leased kitty breeds it, not its owner.
+		fn breeding_parents(who: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> Result<(KittyOf<T>, KittyOf<T>), DispatchError> {
+			ensure!(kitty_id_1 != kitty_id_2, Error::<T>::SameParentIndex);
+			let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyIndex)?;
+			let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(Self::user_of(kitty_id_1).as_ref() == Some(who), Error::<T>::NotOwner);
+			ensure!(Self::user_of(kitty_id_2).as_ref() == Some(who), Error::<T>::NotOwner);
+			Ok((kitty1, kitty2))
+		}
+
+		/// Both kitties have to be unrelated and rested.
+		fn ensure_can_breed(kitty_id_1: T::KittyIndex, kitty1: &KittyOf<T>, kitty_id_2: T::KittyIndex, kitty2: &KittyOf<T>, now: T::BlockNumber) -> DispatchResult {
+			ensure!(!Self::is_related(kitty_id_1, kitty1, kitty_id_2, kitty2), Error::<T>::RelatedParents);
+			ensure!(kitty1.next_breed_block <= now && kitty2.next_breed_block <= now, Error::<T>::BreedingCooldown);
+			Ok(())
+		}
+
+		/// Mint the child of two kitties for `who`, whose `deposit` is already reserved, with dna
+		/// inherited using `seed`. Both parents rest afterwards.
+		fn mint_child(
+			who: &T::AccountId,
+			deposit: BalanceOf<T>,
+			(kitty_id_1, mut kitty1): (T::KittyIndex, KittyOf<T>),
+			(kitty_id_2, mut kitty2): (T::KittyIndex, KittyOf<T>),
+			seed: &[u8; 16],
+		) -> Result<T::KittyIndex, DispatchError> {
+			let now = frame_system::Pallet::<T>::block_number();
+
+			// Dominant/recessive inheritance with a small chance of mutation.
+			let new_dna = crate::genes::inherit(&kitty1.dna, &kitty2.dna, seed);
+
+			let generation = kitty1.generation.max(kitty2.generation).saturating_add(1);
+			let child = Self::mint_kitty(who, deposit, Kitty {
+				dna: new_dna,
+				parents: Some((kitty_id_1, kitty_id_2)),
+				generation,
+				next_breed_block: now.saturating_add(Self::breed_cooldown(generation)),
+				creator: Some(who.clone()),
+				collection: DEFAULT_COLLECTION,
+			})?;
+
+			// Both parents rest before breeding again.
+			kitty1.next_breed_block = now.saturating_add(Self::breed_cooldown(kitty1.generation));
+			kitty2.next_breed_block = now.saturating_add(Self::breed_cooldown(kitty2.generation));
+			Kitties::<T>::insert(kitty_id_1, kitty1);
+			Kitties::<T>::insert(kitty_id_2, kitty2);
+			Ok(child)
+		}
+
+		/// Reserve the deposit of `who` and mint them a generation 0 kitty with random dna.
+		pub(crate) fn do_create(who: &T::AccountId) -> Result<T::KittyIndex, DispatchError> {
+			Self::create_in(who, DEFAULT_COLLECTION)
+		}
+
+		/// Like `do_create`, into a collection with room left. Anyone but the issuer pays the
+		/// mint price.
+		pub(crate) fn do_mint(who: &T::AccountId, collection_id: CollectionId) -> Result<T::KittyIndex, DispatchError> {
+			let mut collection = Self::collections(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(collection.minted < collection.max_supply, Error::<T>::CollectionSoldOut);
+			if *who != collection.issuer {
+				T::Currency::transfer(who, &collection.issuer, collection.mint_price, ExistenceRequirement::KeepAlive)?;
+			}
+			collection.minted += 1;
+			Collections::<T>::insert(collection_id, collection);
+			Self::create_in(who, collection_id)
+		}
+
+		fn create_in(who: &T::AccountId, collection: CollectionId) -> Result<T::KittyIndex, DispatchError> {
+			// stake some balance
+			let deposit = Self::reserve_deposit(who)?;
+
+			// // 获取 dna
+			let dna = Self::random_value(who);
+			Self::mint_kitty(who, deposit, Kitty {
+				dna,
+				parents: None,
+				generation: 0,
+				next_breed_block: frame_system::Pallet::<T>::block_number(),
+				creator: Some(who.clone()),
+				collection,
+			})
+		}
+
+		/// The kitties minted into a collection and not burned, by id.
+		pub fn kitties_in_collection(collection_id: CollectionId) -> Vec<T::KittyIndex> {
+			let mut kitties: Vec<T::KittyIndex> = CollectionKitties::<T>::iter_key_prefix(collection_id).collect();
+			kitties.sort();
+			kitties
+		}
+
+		/// The pot account staking rewards are paid from.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+
+		/// The rewards a staked kitty earned so far.
+		pub fn pending_reward(kitty_id: T::KittyIndex) -> Option<BalanceOf<T>> {
+			let stake = Self::stakes(kitty_id)?;
+			let kitty = Self::kitties(kitty_id)?;
+			let blocks: u32 = frame_system::Pallet::<T>::block_number().saturating_sub(stake.since).saturated_into();
+			let shares = crate::staking::reward_shares(&crate::genes::traits(&kitty));
+			Some(crate::staking::reward(T::StakingRewardPerBlock::get(), shares, blocks))
+		}
+
+		fn do_lock(kitty_id: T::KittyIndex) {
+			Locked::<T>::insert(kitty_id, true);
+			Self::deposit_event(Event::KittyLocked(kitty_id));
+		}
+
+		/// Take a kitty off sale. A dutch listing also leaves the listings expiring with it.
+		fn remove_listing(kitty_id: T::KittyIndex) {
+			if let Some(Listing { mode: PriceMode::Dutch { end, .. }, .. }) = Listings::<T>::take(kitty_id) {
+				DutchListingEnds::<T>::mutate(end.saturating_add(One::one()), |ending| ending.retain(|id| id != &kitty_id));
+			}
+		}
+
+		/// List a kitty of `who` at a fixed price, or update its price.
+		fn do_sell(who: T::AccountId, kitty_id: T::KittyIndex, price: BalanceOf<T>) -> DispatchResult {
+			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+			ensure!(!Stakes::<T>::contains_key(kitty_id), Error::<T>::KittyStaked);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+
+			// Set new or update the price.
+			Self::remove_listing(kitty_id);
+			Listings::<T>::insert(kitty_id, Listing {
+				seller: who.clone(),
+				price,
+				created_at: frame_system::Pallet::<T>::block_number(),
+				mode: PriceMode::Fixed,
+			});
+			Self::deposit_event(Event::ToSellList(who, kitty_id, price));
+			Ok(())
+		}
+
+		/// Delete a kitty of `owner` and release its deposit.
+		pub(crate) fn do_burn(owner: T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!Stakes::<T>::contains_key(kitty_id), Error::<T>::KittyStaked);
+			ensure!(!User::<T>::contains_key(kitty_id), Error::<T>::KittyLeased);
+
+			if let Some(kitty) = Kitties::<T>::take(kitty_id) {
+				CollectionKitties::<T>::remove(kitty.collection, kitty_id);
+				LiveKitties::<T>::mutate(|count| *count = count.saturating_sub(1));
+			}
+			Owner::<T>::remove(kitty_id);
+			Self::remove_listing(kitty_id);
+			Approvals::<T>::remove(kitty_id);
+			LeaseOffers::<T>::remove(kitty_id);
+			Sires::<T>::remove(kitty_id);
+			Locked::<T>::remove(kitty_id);
+			// Nothing is left to buy, release the offers.
+			for (bidder, offer) in Offers::<T>::drain_prefix(kitty_id) {
+				T::Currency::unreserve(&bidder, offer.amount);
+			}
+			Self::clear_metadata(&owner, kitty_id);
+			Self::remove_owned_kitty(&owner, kitty_id);
+			T::Currency::unreserve(&owner, KittyDeposits::<T>::take(kitty_id));
+
+			Self::deposit_event(Event::KittyBurned(owner, kitty_id));
+			Ok(())
+		}
+
 		fn random_value(sender: &T::AccountId) -> [u8; 16] {
 			let payload = (
 				T::Randomness::random_seed(),
@@ -279,25 +1580,310 @@ pub mod pallet {
 			payload.using_encoded(blake2_128)
 		}
 
+		/// Move a kitty, with its deposit, to `new_owner`.
+		#[transactional]
 		pub fn to_transfer(owner: T::AccountId, new_owner: T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
 			// println!("{:?},{:?},{:?}",Some(owner.clone()), Owner::<T>::get(kitty_id.clone()), kitty_id);
 			ensure!(Some(owner.clone()) == Owner::<T>::get(kitty_id.clone()), Error::<T>::NotOwner) ;
-			Owner::<T>::insert(kitty_id, Some(new_owner.clone()));
+			ensure!(!Self::is_locked(kitty_id), Error::<T>::KittyLocked);
+			ensure!(!Stakes::<T>::contains_key(kitty_id), Error::<T>::KittyStaked);
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			if owner != new_owner {
+				Self::add_owned_kitty(&new_owner, kitty_id)?;
+				Self::remove_owned_kitty(&owner, kitty_id);
+				Self::move_deposit(&owner, &new_owner, Self::kitty_deposit(kitty_id))?;
+				if T::ClearMetadataOnTransfer::get() {
+					Self::clear_metadata(&owner, kitty_id);
+				} else if let Some(metadata) = Metadata::<T>::get(kitty_id) {
+					Self::move_deposit(&owner, &new_owner, metadata.deposit)?;
+				}
+			}
+			Owner::<T>::insert(kitty_id, new_owner.clone());
+			// A kitty changing hands is no longer for sale, nor approved, offered for lease or as a
+			// sire to anyone. A running lease goes on with the new owner.
+			Self::remove_listing(kitty_id);
+			Approvals::<T>::remove(kitty_id);
+			LeaseOffers::<T>::remove(kitty_id);
+			Sires::<T>::remove(kitty_id);
 			Self::deposit_event(Event::KittyTransfer(owner, new_owner, kitty_id));
 			Ok(())
 		}
 
-		pub fn get_count_of_kitty () -> u32
-		{
-			let mut kitty_count = match Self::kitties_count() {
-				Some(id) => {
-					id
-				},
-				None => {
-					0
+		/// Move a deposit reserved from `from` to the reserve of `to`. Unlike `repatriate_reserved`
+		/// this works for a new account, as long as the deposit covers the existential deposit.
+		fn move_deposit(from: &T::AccountId, to: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(());
+			}
+			if T::Currency::total_balance(to).is_zero() {
+				ensure!(amount >= T::Currency::minimum_balance(), Error::<T>::DepositBelowExistentialDeposit);
+			}
+			T::Currency::unreserve(from, amount);
+			T::Currency::transfer(from, to, amount, ExistenceRequirement::AllowDeath)?;
+			T::Currency::reserve(to, amount)
+		}
+
+		/// Remove the offer of `bidder` on a kitty, the caller releases its amount.
+		fn take_offer(kitty_id: T::KittyIndex, bidder: &T::AccountId) -> Result<OfferOf<T>, DispatchError> {
+			let offer = Offers::<T>::take(kitty_id, bidder).ok_or(Error::<T>::OfferNotExist)?;
+			OfferExpiries::<T>::mutate(offer.expiry, |lapsing| {
+				lapsing.retain(|(id, who)| !(id == &kitty_id && who == bidder))
+			});
+			Ok(offer)
+		}
+
+		/// Bound `name` and `uri` and adjust the deposit reserved from `who` for the metadata
+		/// they replace.
+		fn reserve_metadata(
+			who: &T::AccountId,
+			old: Option<KittyMetadataOf<T>>,
+			name: &[u8],
+			uri: &[u8],
+		) -> Result<KittyMetadataOf<T>, DispatchError> {
+			let bounded_name: BoundedVec<u8, T::MaxNameLength> = name.to_vec().try_into().map_err(|_| Error::<T>::NameTooLong)?;
+			let bounded_uri: BoundedVec<u8, T::MaxUriLength> = uri.to_vec().try_into().map_err(|_| Error::<T>::UriTooLong)?;
+
+			let deposit = T::MetadataDepositPerByte::get().saturating_mul(((name.len() + uri.len()) as u32).into());
+			let old_deposit = old.map(|metadata| metadata.deposit).unwrap_or_else(Zero::zero);
+			if deposit > old_deposit {
+				T::Currency::reserve(who, deposit - old_deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+			} else {
+				T::Currency::unreserve(who, old_deposit - deposit);
+			}
+			Ok(KittyMetadata { name: bounded_name, uri: bounded_uri, deposit })
+		}
+
+		/// Remove the metadata of a kitty and refund its deposit to `owner`.
+		fn clear_metadata(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+			if let Some(metadata) = Metadata::<T>::take(kitty_id) {
+				T::Currency::unreserve(owner, metadata.deposit);
+				Self::deposit_event(Event::MetadataCleared(kitty_id));
+			}
+		}
+
+		/// Who can breed the kitty, the user while it is leased and its owner otherwise.
+		pub fn user_of(kitty_id: T::KittyIndex) -> Option<T::AccountId> {
+			Self::lease(kitty_id).map(|lease| lease.user).or_else(|| Self::owner(kitty_id))
+		}
+
+		/// Whether `who` owns the kitty, is approved for it or is an operator of its owner.
+		pub fn is_approved_or_owner(who: &T::AccountId, kitty_id: T::KittyIndex) -> bool {
+			match Self::owner(kitty_id) {
+				Some(owner) => {
+					&owner == who
+						|| Self::approvals(kitty_id).as_ref() == Some(who)
+						|| Self::operator_approvals(&owner, who)
+				}
+				None => false,
+			}
+		}
+
+		/// The traits expressed by a kitty's dna.
+		pub fn kitty_traits(kitty_id: T::KittyIndex) -> Option<crate::genes::KittyTraits> {
+			Self::kitties(kitty_id).map(|kitty| crate::genes::traits(&kitty))
+		}
+
+		/// The price a buyer pays for a listed kitty at the current block, `None` if the kitty is
+		/// not for sale or its dutch auction has expired.
+		pub fn current_price(kitty_id: T::KittyIndex) -> Option<BalanceOf<T>> {
+			let listing = Self::listings(kitty_id)?;
+			Self::listing_price(&listing, frame_system::Pallet::<T>::block_number())
+		}
+
+		/// A kitty with its owner and traits, for the runtime api.
+		pub fn kitty_info(kitty_id: T::KittyIndex) -> Option<KittyInfoOf<T>> {
+			let kitty = Self::kitties(kitty_id)?;
+			let owner = Self::owner(kitty_id)?;
+			Some(KittyInfo {
+				kitty_id,
+				owner,
+				traits: crate::genes::traits(&kitty),
+				dna: kitty.dna,
+				parents: kitty.parents,
+				generation: kitty.generation,
+				next_breed_block: kitty.next_breed_block,
+				creator: kitty.creator,
+			})
+		}
+
+		/// The name and uri of a kitty, for the runtime api.
+		pub fn metadata_info(kitty_id: T::KittyIndex) -> Option<KittyMetadataInfo> {
+			Self::kitty_metadata(kitty_id).map(|metadata| KittyMetadataInfo {
+				name: metadata.name.into_inner(),
+				uri: metadata.uri.into_inner(),
+			})
+		}
+
+		/// The kitties of `owner`, for the runtime api.
+		pub fn kitties_of(owner: &T::AccountId) -> Vec<KittyInfoOf<T>> {
+			Self::owned_kitties(owner).iter().filter_map(|kitty_id| Self::kitty_info(*kitty_id)).collect()
+		}
+
+		/// The sale of a kitty at the current block, `None` if it is not for sale or its dutch
+		/// auction has expired.
+		pub fn kitty_sale(kitty_id: T::KittyIndex) -> Option<KittySaleOf<T>> {
+			let listing = Self::listings(kitty_id)?;
+			Self::sale_of(kitty_id, listing, frame_system::Pallet::<T>::block_number())
+		}
+
+		/// A page of the kitties for sale at the current block, expired dutch auctions are left
+		/// out. Meant for the runtime api: every call reads and sorts all listings, which is fine
+		/// while they are few. Paging through many listings would need a price ordered index.
+		pub fn kitties_for_sale(start: u32, limit: u32, sort: ListingSort) -> Vec<KittySaleOf<T>> {
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut sales: Vec<KittySaleOf<T>> = Listings::<T>::iter()
+				.filter_map(|(kitty_id, listing)| Self::sale_of(kitty_id, listing, now))
+				.collect();
+
+			// Ties are broken by kitty id so pages are stable.
+			sales.sort_by(|a, b| {
+				let order = match sort {
+					ListingSort::PriceAsc => a.price.cmp(&b.price),
+					ListingSort::PriceDesc => b.price.cmp(&a.price),
+					ListingSort::Newest => b.listed_at.cmp(&a.listed_at),
+					ListingSort::Oldest => a.listed_at.cmp(&b.listed_at),
+				};
+				order.then(a.kitty_id.cmp(&b.kitty_id))
+			});
+			sales.into_iter().skip(start as usize).take(limit as usize).collect()
+		}
+
+		fn sale_of(kitty_id: T::KittyIndex, listing: ListingOf<T>, now: T::BlockNumber) -> Option<KittySaleOf<T>> {
+			let price = Self::listing_price(&listing, now)?;
+			let ends_at = match listing.mode {
+				PriceMode::Fixed => None,
+				PriceMode::Dutch { end, .. } => Some(end),
+			};
+			Some(KittySale { kitty_id, seller: listing.seller, price, listed_at: listing.created_at, ends_at })
+		}
+
+		fn listing_price(listing: &ListingOf<T>, now: T::BlockNumber) -> Option<BalanceOf<T>> {
+			match listing.mode {
+				PriceMode::Fixed => Some(listing.price),
+				PriceMode::Dutch { floor_price, end } => {
+					if now > end {
+						return None;
+					}
+					let elapsed = now.saturating_sub(listing.created_at).saturated_into::<u128>();
+					let duration = end.saturating_sub(listing.created_at).saturated_into::<u128>();
+					let range = listing.price.saturating_sub(floor_price).saturated_into::<u128>();
+					let decayed = multiply_by_rational(range, elapsed, duration).unwrap_or(range);
+					Some(listing.price.saturating_sub(decayed.saturated_into()))
 				}
+			}
+		}
+
+		/// Hand an ended auction's kitty to the best bidder and pay the seller.
+		/// Without a bid, or if the winner can not take the kitty, it stays with the seller.
+		fn settle_auction(kitty_id: T::KittyIndex) {
+			let auction = match Auctions::<T>::take(kitty_id) {
+				Some(auction) => auction,
+				None => return,
 			};
-			kitty_count
+			if let Some((bidder, amount)) = auction.best_bid {
+				if Self::sell_at_auction(kitty_id, &auction.seller, &bidder, amount).is_ok() {
+					Self::deposit_event(Event::AuctionSettled(kitty_id, bidder, amount));
+					return;
+				}
+				T::Currency::unreserve(&bidder, amount);
+			}
+			Self::deposit_event(Event::AuctionUnsold(kitty_id));
+		}
+
+		/// Hand an auctioned kitty to the winning bidder and pay the bid, reserved from the
+		/// bidder, to the creator and the seller.
+		#[transactional]
+		fn sell_at_auction(kitty_id: T::KittyIndex, seller: &T::AccountId, bidder: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+			Self::to_transfer(seller.clone(), bidder.clone(), kitty_id)?;
+			let mut royalty = Zero::zero();
+			if let Some((creator, due)) = Self::royalty_due(seller, kitty_id, amount) {
+				Self::repatriate_bid(bidder, &creator, due)?;
+				Self::deposit_event(Event::RoyaltyPaid(creator, kitty_id, due));
+				royalty = due;
+			}
+			Self::repatriate_bid(bidder, seller, amount.saturating_sub(royalty))
+		}
+
+		/// Pay `amount` of the bid reserved from `bidder` to `to`, all of it or nothing.
+		fn repatriate_bid(bidder: &T::AccountId, to: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+			let missing = T::Currency::repatriate_reserved(bidder, to, amount, BalanceStatus::Free)?;
+			ensure!(missing.is_zero(), Error::<T>::BidNotReserved);
+			Ok(())
+		}
+
+		/// The creator and their share of a `price` sale of the kitty by `seller`, `None` when the
+		/// creator is unknown or is the seller, or the share is zero.
+		fn royalty_due(seller: &T::AccountId, kitty_id: T::KittyIndex, price: BalanceOf<T>) -> Option<(T::AccountId, BalanceOf<T>)> {
+			let creator = Self::kitties(kitty_id).and_then(|kitty| kitty.creator).filter(|creator| creator != seller)?;
+			let royalty = T::RoyaltyPercent::get() * price;
+			Some((creator, royalty)).filter(|_| !royalty.is_zero())
+		}
+
+		/// Pay the creator share of a `price` sale of the kitty from `buyer`, nothing is due when
+		/// the creator is unknown or is the seller. Returns the paid royalty.
+		fn pay_royalty(buyer: &T::AccountId, seller: &T::AccountId, kitty_id: T::KittyIndex, price: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+			let (creator, royalty) = match Self::royalty_due(seller, kitty_id, price) {
+				Some(due) => due,
+				None => return Ok(Zero::zero()),
+			};
+			T::Currency::transfer(buyer, &creator, royalty, ExistenceRequirement::KeepAlive)?;
+			Self::deposit_event(Event::RoyaltyPaid(creator, kitty_id, royalty));
+			Ok(royalty)
+		}
+
+		/// Reserve the deposit of a new kitty from `who`, returns the reserved amount.
+		fn reserve_deposit(who: &T::AccountId) -> Result<BalanceOf<T>, DispatchError> {
+			let deposit = T::MaxStakeBalance::get();
+			T::Currency::reserve(who, deposit).map_err(|_| Error::<T>::InsufficientDeposit)?;
+			Ok(deposit)
+		}
+
+		/// Store a new kitty under the next id and give it to `owner`, from whom `deposit` is
+		/// reserved.
+		fn mint_kitty(owner: &T::AccountId, deposit: BalanceOf<T>, kitty: KittyOf<T>) -> Result<T::KittyIndex, DispatchError> {
+			let kitty_id = Self::next_kitty_id();
+			let next_kitty_id = kitty_id.checked_add(&One::one()).ok_or(Error::<T>::KittiesCountOverflow)?;
+
+			Self::add_owned_kitty(owner, kitty_id)?;
+			CollectionKitties::<T>::insert(kitty.collection, kitty_id, ());
+			Kitties::<T>::insert(kitty_id, kitty);
+			Owner::<T>::insert(kitty_id, owner.clone());
+			KittyDeposits::<T>::insert(kitty_id, deposit);
+			NextKittyId::<T>::put(next_kitty_id);
+			LiveKitties::<T>::mutate(|count| *count = count.saturating_add(1));
+			// Emit event
+			Self::deposit_event(Event::KittyCreate(owner.clone(), kitty_id));
+			Ok(kitty_id)
+		}
+
+		/// Blocks a kitty of `generation` has to wait between two breedings.
+		pub fn breed_cooldown(generation: u16) -> T::BlockNumber {
+			T::BreedCooldown::get().saturating_mul((generation as u32 + 1).into())
+		}
+
+		/// Whether the two kitties are siblings (sharing a parent) or parent and child.
+		fn is_related(kitty_id_1: T::KittyIndex, kitty_1: &KittyOf<T>, kitty_id_2: T::KittyIndex, kitty_2: &KittyOf<T>) -> bool {
+			let is_parent_of = |kitty: &KittyOf<T>, parent_id: T::KittyIndex| {
+				matches!(kitty.parents, Some((p1, p2)) if p1 == parent_id || p2 == parent_id)
+			};
+			if is_parent_of(kitty_1, kitty_id_2) || is_parent_of(kitty_2, kitty_id_1) {
+				return true;
+			}
+			match (kitty_1.parents, kitty_2.parents) {
+				(Some((a1, a2)), Some((b1, b2))) => a1 == b1 || a1 == b2 || a2 == b1 || a2 == b2,
+				_ => false,
+			}
+		}
+
+		fn add_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+			OwnedKitties::<T>::try_mutate(owner, |owned| {
+				owned.try_push(kitty_id).map_err(|_| Error::<T>::TooManyKitties)
+			})?;
+			Ok(())
+		}
+
+		fn remove_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+			OwnedKitties::<T>::mutate(owner, |owned| owned.retain(|id| id != &kitty_id));
 		}
 	}
 }
@@ -0,0 +1,264 @@
+//! Storage migrations for pallet-kitties.
+//!
+//! Every version translates the stored kitties straight into the current layout, so only the
+//! one matching the on-chain version runs. [`migrate`] then moves the rest of the storage over.
+
+use super::*;
+use sp_runtime::traits::Zero;
+use frame_support::{
+	storage::migration::take_storage_value,
+	traits::{Get, PalletInfoAccess, StorageVersion},
+	weights::Weight,
+};
+#[cfg(feature = "try-runtime")]
+use frame_support::{ensure, storage::migration::get_storage_value};
+
+/// Upgrade the storage from `version` to the current one.
+pub fn migrate<T: Config>(version: StorageVersion) -> Weight {
+	let mut weight = if version < StorageVersion::new(1) {
+		v1::migrate::<T>()
+	} else if version < StorageVersion::new(2) {
+		v2::migrate::<T>()
+	} else if version < StorageVersion::new(3) {
+		v3::migrate::<T>()
+	} else {
+		v4::migrate::<T>()
+	};
+	if version < StorageVersion::new(3) {
+		weight = weight.saturating_add(v3::migrate_owners::<T>());
+	}
+	if version < StorageVersion::new(1) {
+		weight = weight.saturating_add(v1::index_owners::<T>());
+	}
+	// Deposits were reserved for every kitty from version 2 on, the ones before drifted.
+	let deposit = if version < StorageVersion::new(2) { Zero::zero() } else { T::MaxStakeBalance::get() };
+	weight = weight.saturating_add(v4::index_kitties::<T>(deposit));
+	StorageVersion::new(4).put::<Pallet<T>>();
+	weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+}
+
+/// Version 1 records the genealogy and breeding cooldown of every kitty.
+pub mod v1 {
+	use super::*;
+	use codec::{Decode, Encode};
+	use sp_runtime::traits::Zero;
+	use frame_support::storage::bounded_vec::BoundedVec;
+	use sp_std::{collections::btree_map::BTreeMap, convert::TryInto, vec::Vec};
+
+	/// A kitty as stored in version 0, only its dna.
+	#[derive(Encode, Decode)]
+	pub struct OldKitty(pub [u8; 16]);
+
+	/// Existing kitties become generation 0 kitties without parents that can breed right away.
+	pub fn migrate<T: Config>() -> Weight {
+		let mut translated: u64 = 0;
+		Kitties::<T>::translate::<Option<OldKitty>, _>(|_, old| {
+			translated += 1;
+			old.map(|kitty| Kitty {
+				dna: kitty.0,
+				parents: None,
+				generation: 0,
+				next_breed_block: Zero::zero(),
+				creator: None,
+				collection: DEFAULT_COLLECTION,
+			})
+		});
+		T::DbWeight::get().reads_writes(translated, translated)
+	}
+
+	/// Rebuild `OwnedKitties` from `Owner`, after the owners are translated. An owner of more
+	/// than `MaxKittiesOwned` kitties gets the lowest ids indexed, the others stay theirs but
+	/// are missing from `owned_kitties` until they change hands.
+	pub fn index_owners<T: Config>() -> Weight {
+		let mut owned = BTreeMap::<T::AccountId, Vec<T::KittyIndex>>::new();
+		let mut indexed: u64 = 0;
+		for (kitty_id, owner) in Owner::<T>::iter() {
+			owned.entry(owner).or_default().push(kitty_id);
+			indexed += 1;
+		}
+		let owners = owned.len() as u64;
+		for (owner, mut kitty_ids) in owned {
+			kitty_ids.sort();
+			kitty_ids.truncate(T::MaxKittiesOwned::get() as usize);
+			let kitty_ids: BoundedVec<_, T::MaxKittiesOwned> = kitty_ids.try_into().expect("truncated to the bound");
+			OwnedKitties::<T>::insert(owner, kitty_ids);
+		}
+		T::DbWeight::get().reads_writes(indexed, owners)
+	}
+}
+
+/// Version 2 records the creator of every kitty, who earns royalties on its sales.
+pub mod v2 {
+	use super::*;
+	use codec::{Decode, Encode};
+
+	/// A kitty as stored in version 1.
+	#[derive(Encode, Decode)]
+	pub struct OldKitty<KittyIndex, BlockNumber> {
+		pub dna: [u8; 16],
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+		pub generation: u16,
+		pub next_breed_block: BlockNumber,
+	}
+
+	/// The creators of existing kitties are unknown, no royalties are paid on them.
+	pub fn migrate<T: Config>() -> Weight {
+		let mut translated: u64 = 0;
+		Kitties::<T>::translate::<Option<OldKitty<T::KittyIndex, T::BlockNumber>>, _>(|_, old| {
+			translated += 1;
+			old.map(|kitty| Kitty {
+				dna: kitty.dna,
+				parents: kitty.parents,
+				generation: kitty.generation,
+				next_breed_block: kitty.next_breed_block,
+				creator: None,
+				collection: DEFAULT_COLLECTION,
+			})
+		});
+		T::DbWeight::get().reads_writes(translated, translated)
+	}
+}
+
+/// Version 3 stores `Kitties` and `Owner` without the extra `Option` and replaces the `u32`
+/// `KittiesCount` with `NextKittyId`.
+pub mod v3 {
+	use super::*;
+	use sp_runtime::traits::{One, Saturating};
+
+	/// The name of the `KittiesCount` storage value up to version 2.
+	pub const KITTIES_COUNT: &[u8] = b"KittiesCount";
+
+	pub fn migrate<T: Config>() -> Weight {
+		let mut translated: u64 = 0;
+		Kitties::<T>::translate::<Option<v4::OldKittyOf<T>>, _>(|_, old| {
+			translated += 1;
+			old.map(v4::OldKitty::upgrade)
+		});
+		T::DbWeight::get().reads_writes(translated, translated)
+	}
+
+	/// Translate `Owner` and turn the count of created kitties into the next kitty id. Owner
+	/// entries did not change before version 3, so this runs for every older version.
+	pub fn migrate_owners<T: Config>() -> Weight {
+		let mut translated: u64 = 0;
+		Owner::<T>::translate::<Option<T::AccountId>, _>(|_, old| {
+			translated += 1;
+			old
+		});
+
+		let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+		let count = take_storage_value::<u32>(pallet, KITTIES_COUNT, &[]).unwrap_or(0);
+		NextKittyId::<T>::put(T::KittyIndex::from(count).saturating_add(One::one()));
+
+		T::DbWeight::get().reads_writes(translated + 1, translated + 2)
+	}
+
+	/// Every kitty has an owner and an id below the old count.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+		use frame_support::{storage::migration::storage_key_iter, Blake2_128Concat};
+
+		if StorageVersion::get::<Pallet<T>>() >= StorageVersion::new(3) {
+			return Ok(());
+		}
+		let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+		let count = get_storage_value::<u32>(pallet, KITTIES_COUNT, &[]).unwrap_or(0);
+		for (kitty_id, owner) in storage_key_iter::<T::KittyIndex, Option<T::AccountId>, Blake2_128Concat>(pallet, b"Owner") {
+			ensure!(owner.is_some(), "kitty without owner");
+			ensure!(kitty_id <= count.into(), "kitty id above KittiesCount");
+		}
+		Ok(())
+	}
+
+	/// The new storage decodes, matches between `Kitties` and `Owner` and ids are below
+	/// `NextKittyId`.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::get::<Pallet<T>>() >= StorageVersion::new(3), "storage version not updated");
+		let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+		ensure!(get_storage_value::<u32>(pallet, KITTIES_COUNT, &[]).is_none(), "KittiesCount left behind");
+
+		let next_kitty_id = Pallet::<T>::next_kitty_id();
+		for (kitty_id, _) in Kitties::<T>::iter() {
+			ensure!(Owner::<T>::contains_key(kitty_id), "kitty without owner");
+			ensure!(kitty_id < next_kitty_id, "kitty id not below NextKittyId");
+		}
+		for (kitty_id, owner) in Owner::<T>::iter() {
+			ensure!(Kitties::<T>::contains_key(kitty_id), "owner without kitty");
+			let owned = Pallet::<T>::owned_kitties(&owner);
+			ensure!(
+				owned.contains(&kitty_id) || owned.len() as u32 == T::MaxKittiesOwned::get(),
+				"kitty missing from OwnedKitties",
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Version 4 puts every kitty in a collection, existing kitties join the default collection.
+pub mod v4 {
+	use super::*;
+	use codec::{Decode, Encode};
+
+	/// A kitty as stored in version 3, before collections.
+	#[derive(Encode, Decode)]
+	pub struct OldKitty<AccountId, KittyIndex, BlockNumber> {
+		pub dna: [u8; 16],
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+		pub generation: u16,
+		pub next_breed_block: BlockNumber,
+		pub creator: Option<AccountId>,
+	}
+
+	pub type OldKittyOf<T> = OldKitty<<T as frame_system::Config>::AccountId, <T as Config>::KittyIndex, <T as frame_system::Config>::BlockNumber>;
+
+	impl<AccountId, KittyIndex, BlockNumber> OldKitty<AccountId, KittyIndex, BlockNumber> {
+		/// The same kitty in the default collection.
+		pub fn upgrade(self) -> Kitty<AccountId, KittyIndex, BlockNumber> {
+			Kitty {
+				dna: self.dna,
+				parents: self.parents,
+				generation: self.generation,
+				next_breed_block: self.next_breed_block,
+				creator: self.creator,
+				collection: DEFAULT_COLLECTION,
+			}
+		}
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		let mut translated: u64 = 0;
+		Kitties::<T>::translate::<OldKittyOf<T>, _>(|_, old| {
+			translated += 1;
+			Some(old.upgrade())
+		});
+		T::DbWeight::get().reads_writes(translated, translated)
+	}
+
+	/// Index every kitty under its collection, record `deposit` as its deposit and count them.
+	/// Runs for every older version, after the kitties are translated.
+	pub fn index_kitties<T: Config>(deposit: BalanceOf<T>) -> Weight {
+		let mut indexed: u64 = 0;
+		for (kitty_id, kitty) in Kitties::<T>::iter() {
+			CollectionKitties::<T>::insert(kitty.collection, kitty_id, ());
+			KittyDeposits::<T>::insert(kitty_id, deposit);
+			indexed += 1;
+		}
+		LiveKitties::<T>::put(indexed as u32);
+		T::DbWeight::get().reads_writes(indexed, 2 * indexed + 1)
+	}
+
+	/// Every kitty is indexed under its collection and has a deposit recorded.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::get::<Pallet<T>>() == StorageVersion::new(4), "storage version not updated");
+		let mut count: u32 = 0;
+		for (kitty_id, kitty) in Kitties::<T>::iter() {
+			ensure!(CollectionKitties::<T>::contains_key(kitty.collection, kitty_id), "kitty not in its collection");
+			ensure!(KittyDeposits::<T>::contains_key(kitty_id), "kitty without deposit");
+			count += 1;
+		}
+		ensure!(Pallet::<T>::kitties_count() == count, "LiveKitties does not match the kitties");
+		Ok(())
+	}
+}
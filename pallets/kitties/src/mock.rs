@@ -5,6 +5,7 @@ use frame_system as system;
 pub use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, Header as _, IdentityLookup},
+	Permill,
 };
 
 pub use frame_support::{
@@ -26,7 +27,7 @@ frame_support::construct_runtime!(
 	{
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		Kitties: pallet_kitties::{Pallet, Call, Storage, Event<T>},
+		Kitties: pallet_kitties::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
@@ -73,16 +74,70 @@ impl system::Config for Test {
 
 impl pallet_randomness_collective_flip::Config for Test {}
 
+parameter_types! {
+	// When set, the randomness used by the kitties is fixed to this seed.
+	pub static FixedRandomSeed: Option<H256> = None;
+}
+
+/// RandomnessCollectiveFlip, unless `FixedRandomSeed` is set.
+pub struct MockRandomness;
+impl Randomness<H256, u64> for MockRandomness {
+	fn random(subject: &[u8]) -> (H256, u64) {
+		match FixedRandomSeed::get() {
+			Some(seed) => (seed, 0),
+			None => RandomnessCollectiveFlip::random(subject),
+		}
+	}
+}
+
 parameter_types! {
 	pub const Deposit: u64 = 50;
+	pub const MaxKittiesOwned: u32 = 3;
+	pub const MaxAuctionsPerBlock: u32 = 2;
+	pub const BreedCooldown: u64 = 5;
+	pub const MaxLeasesPerBlock: u32 = 2;
+	pub const MaxOffersPerBlock: u32 = 2;
+	pub const MinOfferAmount: u64 = 10;
+	pub const MaxNameLength: u32 = 8;
+	pub const MaxUriLength: u32 = 16;
+	pub const MetadataDepositPerByte: u64 = 1;
+	pub const CollectionDeposit: u64 = 20;
+	pub static ClearMetadataOnTransfer: bool = true;
+	pub static RoyaltyPercent: Permill = Permill::from_percent(10);
+	pub const RevealDelay: u64 = 2;
+	pub const RevealTimeout: u64 = 5;
+	pub const MaxBreedCommitsPerBlock: u32 = 2;
+	pub const MaxBatchSize: u32 = 3;
+	pub const KittiesPalletId: frame_support::PalletId = frame_support::PalletId(*b"py/kitty");
+	pub const StakingRewardPerBlock: u64 = 1;
 }
 
 impl pallet_kitties::Config for Test {
 	type Event = Event;
-	type Randomness = RandomnessCollectiveFlip;
+	type Randomness = MockRandomness;
 	type KittyIndex = u32;
 	type Currency = Balances;
 	type MaxStakeBalance = Deposit;
+	type MaxKittiesOwned = MaxKittiesOwned;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type BreedCooldown = BreedCooldown;
+	type MaxLeasesPerBlock = MaxLeasesPerBlock;
+	type MaxOffersPerBlock = MaxOffersPerBlock;
+	type MinOfferAmount = MinOfferAmount;
+	type MaxNameLength = MaxNameLength;
+	type MaxUriLength = MaxUriLength;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type CollectionDeposit = CollectionDeposit;
+	type ClearMetadataOnTransfer = ClearMetadataOnTransfer;
+	type RoyaltyPercent = RoyaltyPercent;
+	type RevealDelay = RevealDelay;
+	type RevealTimeout = RevealTimeout;
+	type MaxBreedCommitsPerBlock = MaxBreedCommitsPerBlock;
+	type MaxBatchSize = MaxBatchSize;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type PalletId = KittiesPalletId;
+	type StakingRewardPerBlock = StakingRewardPerBlock;
+	type WeightInfo = ();
 	// type MaxStakeBalance = u64;
 }
 
@@ -111,9 +166,17 @@ use frame_benchmarking::frame_support::sp_runtime::traits::AtLeast32BitUnsigned;
 use frame_benchmarking::frame_support::Parameter;
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with_kitties(vec![])
+}
+
+/// Like `new_test_ext`, with `kitties` created at genesis.
+pub fn new_test_ext_with_kitties(kitties: Vec<(u64, [u8; 16])>) -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_balances::GenesisConfig::<Test>{
 		balances: vec![(1, 100), (2, 200), (3, 300)],
 	}.assimilate_storage(&mut t).unwrap();
+	pallet_kitties::GenesisConfig::<Test>{
+		kitties,
+	}.assimilate_storage(&mut t).unwrap();
 	t.into()
 }
@@ -0,0 +1,33 @@
+//! Staking of kitties for rewards.
+//!
+//! A staked kitty can not change hands or be sold. Every block it earns `StakingRewardPerBlock`
+//! for each of its reward shares, rarer kitties hold more shares. The rewards are paid from the
+//! pallet pot account when the kitty is unstaked. The pot only holds what was put in it, at
+//! genesis or by transfers, and pays at most its free balance.
+
+use codec::{Decode, Encode};
+use sp_runtime::{traits::AtLeast32BitUnsigned, RuntimeDebug};
+
+use crate::genes::{KittyTraits, RarityTier};
+
+/// A staked kitty, rewards accrue from `since`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Stake<AccountId, BlockNumber> {
+	pub owner: AccountId,
+	pub since: BlockNumber,
+}
+
+/// Reward shares of a kitty, relative to a common one.
+pub fn reward_shares(traits: &KittyTraits) -> u32 {
+	match traits.rarity {
+		RarityTier::Common => 1,
+		RarityTier::Uncommon => 2,
+		RarityTier::Rare => 5,
+		RarityTier::Legendary => 10,
+	}
+}
+
+/// The reward of `shares` staked for `blocks` blocks at `per_block` per share.
+pub fn reward<Balance: AtLeast32BitUnsigned>(per_block: Balance, shares: u32, blocks: u32) -> Balance {
+	per_block.saturating_mul(shares.into()).saturating_mul(blocks.into())
+}
@@ -10,11 +10,11 @@ fn test_create_kitties() {
 		setup_blocks(162);
 		assert_eq!(System::block_number(), 162);
 		// Begin testiing, count = 0
-		assert_eq!(Kitties::kitties_count(), None);
+		assert_eq!(Kitties::next_kitty_id(), 1);
 		// Create a kitty cat.
 		assert_ok!(Kitties::create(Origin::signed(1)));
 		// Kitties count add 1
-		assert_eq!(Kitties::kitties_count(), Some(1));
+		assert_eq!(Kitties::next_kitty_id(), 2);
 		// Kitties owner is Origin::signed(1)
 		assert_eq!(Kitties::owner(1), Some(1));
 		assert!(Kitties::kitties(1).is_some());
@@ -41,7 +41,7 @@ fn test_transfer_kitties() {
 		// Stake after.
 		assert_eq!(Balances::free_balance(1), 50);
 		// Kitties count add 1
-		assert_eq!(Kitties::kitties_count(), Some(1));
+		assert_eq!(Kitties::next_kitty_id(), 2);
 		// And owner is 1
 		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_1));
 		// Transfer kitties. old owner transfer to new owner
@@ -49,7 +49,7 @@ fn test_transfer_kitties() {
 		// Test kitties owner.
 		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_2));
 		// The quantity is still 1 after transmission.
-		assert_eq!(Kitties::kitties_count(), Some(1));
+		assert_eq!(Kitties::next_kitty_id(), 2);
 
 		// Sending mutilple times will cause an error because owner is changed.
 		assert_noop!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, KITTY_ID_1), Error::<Test>::NotOwner);
@@ -77,7 +77,7 @@ fn test_bread_kitties() {
 		// Create kitties
 		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
 		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_2)));
-		assert_eq!(Kitties::kitties_count(), Some(2));
+		assert_eq!(Kitties::next_kitty_id(), 3);
 
 		// Test kitty not exists.
 		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, KITTY_ID_3), Error::<Test>::InvalidKittyIndex);
@@ -88,11 +88,17 @@ fn test_bread_kitties() {
 		// Test kitty not same one.
 		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, KITTY_ID_1), Error::<Test>::SameParentIndex);
 
+		// Only the owner of both kitties can breed them.
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, KITTY_ID_2), Error::<Test>::NotOwner);
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_3, KITTY_ID_1));
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, KITTY_ID_2), Error::<Test>::NotOwner);
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_3, KITTY_ID_2));
+
 		// kitty1 + kitty2 = bron kitty3
-		Kitties::bread(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, KITTY_ID_2);
+		assert_ok!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, KITTY_ID_2));
 
 		// kitty count is 3
-		assert_eq!(Kitties::kitties_count(), Some(3));
+		assert_eq!(Kitties::next_kitty_id(), 4);
 
 		// kitty 3 owner is Origin(3)
 		assert_eq!(Kitties::owner(KITTY_ID_3), Some(ACCOUNT_ID_3));
@@ -103,6 +109,276 @@ fn test_bread_kitties() {
 	});
 }
 
+#[test]
+fn test_bread_genealogy_and_cooldown() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		let kitty = Kitties::kitties(1).unwrap();
+		assert_eq!(kitty.parents, None);
+		assert_eq!(kitty.generation, 0);
+		assert_eq!(kitty.next_breed_block, 162);
+
+		// kitty1 + kitty2 = kitty3, generation 1.
+		assert_ok!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), 1, 2));
+		let kitty = Kitties::kitties(3).unwrap();
+		assert_eq!(kitty.parents, Some((1, 2)));
+		assert_eq!(kitty.generation, 1);
+		// BreedCooldown is 5 blocks per generation.
+		assert_eq!(kitty.next_breed_block, 162 + 10);
+		assert_eq!(Kitties::kitties(1).unwrap().next_breed_block, 162 + 5);
+		assert_eq!(Kitties::kitties(2).unwrap().next_breed_block, 162 + 5);
+
+		// A parent can not breed with its child, and the parents rest.
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), 1, 3), Error::<Test>::RelatedParents);
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), 3, 2), Error::<Test>::RelatedParents);
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), 1, 2), Error::<Test>::BreedingCooldown);
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_3), ACCOUNT_ID_2, 3));
+
+		// kitty1 + kitty2 = kitty4 once they rested.
+		setup_blocks(167);
+		assert_ok!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), 1, 2));
+
+		// Siblings can not breed.
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_3), ACCOUNT_ID_2, 4));
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_2), 3, 4), Error::<Test>::RelatedParents);
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, 4));
+
+		// kitty3 is generation 1 and rests longer than the new kitty5.
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_2), 3, 5), Error::<Test>::BreedingCooldown);
+		setup_blocks(172);
+		assert_ok!(Kitties::bread(Origin::signed(ACCOUNT_ID_2), 3, 5));
+		let kitty = Kitties::kitties(6).unwrap();
+		assert_eq!(kitty.parents, Some((3, 5)));
+		assert_eq!(kitty.generation, 2);
+		assert_eq!(kitty.next_breed_block, 172 + 15);
+		assert_eq!(Kitties::kitties(3).unwrap().next_breed_block, 172 + 10);
+		assert_eq!(Kitties::kitties(5).unwrap().next_breed_block, 172 + 5);
+	});
+}
+
+#[test]
+fn test_migrate_kitties_to_v1() {
+	new_test_ext().execute_with(|| {
+		use codec::Encode;
+		use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+		// A v0 kitty, only its dna.
+		let old_kitty = Some(crate::migrations::v1::OldKitty([7u8; 16]));
+		frame_support::storage::unhashed::put_raw(&crate::Kitties::<Test>::hashed_key_for(1u32), &old_kitty.encode());
+		assert_eq!(StorageVersion::get::<Kitties>(), StorageVersion::new(0));
+
+		Kitties::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<Kitties>(), StorageVersion::new(4));
+		assert_eq!(Kitties::kitties(1), Some(crate::Kitty { dna: [7u8; 16], parents: None, generation: 0, next_breed_block: 0, creator: None, collection: 0 }));
+	});
+}
+
+#[test]
+fn test_migrate_kitties_to_v2() {
+	new_test_ext().execute_with(|| {
+		use codec::Encode;
+		use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+		// A v1 kitty, without its creator.
+		let old_kitty = Some(crate::migrations::v2::OldKitty::<u32, u64> { dna: [7u8; 16], parents: Some((1, 2)), generation: 1, next_breed_block: 10 });
+		frame_support::storage::unhashed::put_raw(&crate::Kitties::<Test>::hashed_key_for(3u32), &old_kitty.encode());
+		StorageVersion::new(1).put::<Kitties>();
+
+		Kitties::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<Kitties>(), StorageVersion::new(4));
+		assert_eq!(Kitties::kitties(3), Some(crate::Kitty { dna: [7u8; 16], parents: Some((1, 2)), generation: 1, next_breed_block: 10, creator: None, collection: 0 }));
+	});
+}
+
+#[test]
+fn test_migrate_populated_v0_to_v3() {
+	new_test_ext().execute_with(|| {
+		use codec::Encode;
+		use frame_support::{storage::{migration::{get_storage_value, put_storage_value}, unhashed}, traits::{OnRuntimeUpgrade, StorageVersion}};
+
+		// Three v0 kitties of two owners, the second one was burned.
+		for (kitty_id, owner) in [(1u32, 1u64), (3, 2), (4, 1)].iter() {
+			let old_kitty = Some(crate::migrations::v1::OldKitty([*kitty_id as u8; 16]));
+			unhashed::put_raw(&crate::Kitties::<Test>::hashed_key_for(kitty_id), &old_kitty.encode());
+			unhashed::put_raw(&crate::Owner::<Test>::hashed_key_for(kitty_id), &Some(*owner).encode());
+		}
+		put_storage_value(b"Kitties", b"KittiesCount", &[], 4u32);
+
+		Kitties::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<Kitties>(), StorageVersion::new(4));
+		assert_eq!(get_storage_value::<u32>(b"Kitties", b"KittiesCount", &[]), None);
+		assert_eq!(Kitties::next_kitty_id(), 5);
+		assert_eq!(Kitties::kitties(3).map(|kitty| kitty.dna), Some([3u8; 16]));
+		assert_eq!(Kitties::owner(1), Some(1));
+		assert_eq!(Kitties::owner(3), Some(2));
+		assert_eq!(Kitties::owner(4), Some(1));
+		assert!(!crate::Kitties::<Test>::contains_key(2));
+		assert!(!crate::Owner::<Test>::contains_key(2));
+		assert_eq!(Kitties::kitties_in_collection(0), vec![1, 3, 4]);
+		assert_eq!(Kitties::kitties_count(), 3);
+		assert_eq!(Kitties::owned_kitties(1).into_inner(), vec![1, 4]);
+		assert_eq!(Kitties::owned_kitties(2).into_inner(), vec![3]);
+		assert_eq!(Kitties::kitties_of(&1).len(), 2);
+
+		// The deposits of v0 kitties drifted, none is recorded.
+		assert_eq!(Kitties::kitty_deposit(4), 0);
+
+		// The kitties are usable after the upgrade, new ones continue the ids.
+		setup_blocks(2);
+		assert_ok!(Kitties::transfer(Origin::signed(1), 3, 4));
+		assert_eq!(Kitties::owner(4), Some(3));
+		assert_eq!(Kitties::owned_kitties(1).into_inner(), vec![1]);
+		assert_eq!((Balances::free_balance(1), Balances::reserved_balance(1)), (100, 0));
+		assert_eq!((Balances::free_balance(3), Balances::reserved_balance(3)), (300, 0));
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_eq!(Kitties::owner(5), Some(3));
+		assert_eq!(Kitties::owned_kitties(3).into_inner(), vec![4, 5]);
+		assert_eq!(Kitties::kitty_deposit(5), 50);
+
+		// Burning a v0 kitty releases nothing that belongs to something else.
+		assert_ok!(Kitties::make_offer(Origin::signed(3), 1, 20, 10));
+		assert_ok!(Kitties::burn(Origin::signed(3), 4));
+		assert_eq!(Balances::reserved_balance(3), 70);
+
+		// Running it again does nothing.
+		Kitties::on_runtime_upgrade();
+		assert_eq!(Kitties::next_kitty_id(), 6);
+	});
+}
+
+#[test]
+fn test_migrate_v0_owner_over_the_bound() {
+	new_test_ext().execute_with(|| {
+		use codec::Encode;
+		use frame_support::{storage::{migration::put_storage_value, unhashed}, traits::OnRuntimeUpgrade};
+
+		// Account 1 owns one kitty more than `MaxKittiesOwned`.
+		for kitty_id in [4u32, 2, 3, 1].iter() {
+			let old_kitty = Some(crate::migrations::v1::OldKitty([*kitty_id as u8; 16]));
+			unhashed::put_raw(&crate::Kitties::<Test>::hashed_key_for(kitty_id), &old_kitty.encode());
+			unhashed::put_raw(&crate::Owner::<Test>::hashed_key_for(kitty_id), &Some(1u64).encode());
+		}
+		put_storage_value(b"Kitties", b"KittiesCount", &[], 4u32);
+
+		Kitties::on_runtime_upgrade();
+
+		// The lowest ids are indexed, the last kitty stays owned.
+		assert_eq!(Kitties::owned_kitties(1).into_inner(), vec![1, 2, 3]);
+		assert_eq!(Kitties::owner(4), Some(1));
+		assert_noop!(Kitties::create(Origin::signed(1)), Error::<Test>::TooManyKitties);
+
+		// It is indexed again once it changes hands.
+		assert_ok!(Kitties::transfer(Origin::signed(1), 2, 4));
+		assert_eq!(Kitties::owned_kitties(2).into_inner(), vec![4]);
+		assert_eq!(Kitties::owned_kitties(1).into_inner(), vec![1, 2, 3]);
+	});
+}
+
+#[test]
+fn test_migrate_kitties_to_v4() {
+	new_test_ext().execute_with(|| {
+		use codec::Encode;
+		use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+		// A v3 kitty, before collections.
+		let old_kitty = crate::migrations::v4::OldKitty::<u64, u32, u64> { dna: [7u8; 16], parents: None, generation: 0, next_breed_block: 10, creator: Some(2) };
+		frame_support::storage::unhashed::put_raw(&crate::Kitties::<Test>::hashed_key_for(4u32), &old_kitty.encode());
+		crate::Owner::<Test>::insert(4, 2);
+		crate::NextKittyId::<Test>::put(5);
+		StorageVersion::new(3).put::<Kitties>();
+
+		Kitties::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<Kitties>(), StorageVersion::new(4));
+		assert_eq!(Kitties::kitties(4), Some(crate::Kitty { dna: [7u8; 16], parents: None, generation: 0, next_breed_block: 10, creator: Some(2), collection: 0 }));
+		assert_eq!(Kitties::kitties_in_collection(0), vec![4]);
+		assert_eq!(Kitties::kitties_count(), 1);
+		assert_eq!(Kitties::kitty_deposit(4), 50);
+		// Only the kitties changed.
+		assert_eq!(Kitties::next_kitty_id(), 5);
+		assert_eq!(Kitties::owner(4), Some(2));
+	});
+}
+
+#[test]
+fn test_gene_traits() {
+	use crate::genes::{dna_traits, BodyColor, EyeShape, KittyTraits, Pattern, RarityTier};
+
+	// The lower allele of every gene is expressed, in any order.
+	let dna = [0x00, 0xff, 0x80, 0x40, 0xc0, 0xc0, 0xff, 0xf8, 0, 0, 0, 0, 0, 0, 0, 0];
+	assert_eq!(dna_traits(&dna), KittyTraits {
+		body_color: BodyColor::Black,
+		pattern: Pattern::Tabby,
+		eye_shape: EyeShape::Sleepy,
+		rarity: RarityTier::Legendary,
+	});
+
+	// A rare allele is hidden by a common one.
+	let dna = [0xff, 0xe0, 0x3f, 0xff, 0x40, 0x7f, 0x00, 0xff, 0, 0, 0, 0, 0, 0, 0, 0];
+	assert_eq!(dna_traits(&dna), KittyTraits {
+		body_color: BodyColor::Lilac,
+		pattern: Pattern::Solid,
+		eye_shape: EyeShape::Almond,
+		rarity: RarityTier::Common,
+	});
+}
+
+#[test]
+fn test_gene_inherit() {
+	use crate::genes::inherit;
+
+	let dna_1 = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+	let dna_2 = [101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116];
+
+	// First allele of both parents, no mutation (roll 1).
+	let child = inherit(&dna_1, &dna_2, &[0b100; 16]);
+	assert_eq!(child, [1, 101, 3, 103, 5, 105, 7, 107, 9, 109, 11, 111, 13, 113, 15, 115]);
+
+	// Second allele of both parents, the dominant allele is stored first.
+	let child = inherit(&dna_2, &dna_1, &[0b111; 16]);
+	assert_eq!(child, [2, 102, 4, 104, 6, 106, 8, 108, 10, 110, 12, 112, 14, 114, 16, 116]);
+
+	// A roll of 0 mutates the first parent's allele of the gene.
+	let mut random = [0b100; 16];
+	random[2] = 0b000;
+	random[3] = 255;
+	let child = inherit(&dna_1, &dna_2, &random);
+	assert_eq!(&child[..4], &[1, 101, 103, 255]);
+}
+
+#[test]
+fn test_bread_with_fixed_randomness() {
+	// Breed two kitties with the same fixed seed and return the child.
+	let breed = |seed: H256| {
+		new_test_ext().execute_with(|| {
+			setup_blocks(162);
+			FixedRandomSeed::set(&Some(seed));
+			assert_ok!(Kitties::create(Origin::signed(3)));
+			assert_ok!(Kitties::create(Origin::signed(3)));
+			assert_ok!(Kitties::bread(Origin::signed(3), 1, 2));
+
+			let child = Kitties::kitties(3).unwrap();
+			assert_eq!(Kitties::kitty_traits(3), Some(crate::genes::traits(&child)));
+			child.dna
+		})
+	};
+
+	// Deterministic for a given seed.
+	assert_eq!(breed(H256::repeat_byte(1)), breed(H256::repeat_byte(1)));
+	assert_ne!(breed(H256::repeat_byte(1)), breed(H256::repeat_byte(2)));
+}
+
 // if balance import success this testing will be ok.
 #[test]
 fn test_balance_total () {
@@ -150,58 +426,1510 @@ fn test_kitty_sell_and_buy () {
 		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_1));
 		assert_eq!(Kitties::owner(KITTY_ID_2), Some(ACCOUNT_ID_1));
 
-		// get ACCOUNT_ID_1 sell list, now is empty.
-		let sell_list = Kitties::sell_list(ACCOUNT_ID_1);
-		assert_eq!(sell_list.len(), 0 , "sell list is empty.");
+		// Nothing is for sale yet.
+		assert_eq!(Kitties::listings(KITTY_ID_2), None);
+
+		// Only the owner can sell a kitty.
+		assert_noop!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, 230), Error::<Test>::NotOwner);
 
 		// add KITTY_ID_2 to sell list.
 		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_1), KITTY_ID_2, 230));
 
-		System::assert_has_event(Event::Kitties(crate::Event::<Test>::ToSellList(ACCOUNT_ID_1, KITTY_ID_2)));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::ToSellList(ACCOUNT_ID_1, KITTY_ID_2, 230)));
 
 		// the query owner is still him.
 		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_1));
 		assert_eq!(Kitties::owner(KITTY_ID_2), Some(ACCOUNT_ID_1));
 
-		// the query sell list of him, KITTY_ID_2 exists.
-		let mut sell_list = Kitties::sell_list(ACCOUNT_ID_1);
-		assert_eq!(sell_list.clone().len(), 1 , "KITTY_ID_2 exists. ");
-		// let sell_list = Kitties::sell_list(ACCOUNT_ID_1);
-		if let Some((sell_kitty_id, sell_balance)) = sell_list.pop() {
-			assert_eq!(sell_kitty_id, KITTY_ID_2 );
-			assert_eq!(sell_balance, 230 );
-		} else {
-			assert!(false, "This is impossible.");
-		}
+		// the listing of KITTY_ID_2 exists.
+		assert_eq!(Kitties::listings(KITTY_ID_2), Some(crate::Listing { seller: ACCOUNT_ID_1, price: 230, created_at: 162, mode: crate::PriceMode::Fixed }));
 
 		// account_2 want to buy KITTY_ID_1, but KITTY_ID_1 has not sold yet.
-		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, KITTY_ID_1), Error::<Test>::KittyHasNotSold);
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 230), Error::<Test>::KittyHasNotSold);
+
+		// The seller can not buy their own kitty.
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_1), KITTY_ID_2, 230), Error::<Test>::BuyOwnKitty);
 
 		// account_2 has 200 yuan, try to buy KITTY_ID_2
 		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200);
 		// But the balance is insufficient
-		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, KITTY_ID_2), pallet_balances::Error::<Test>::InsufficientBalance);
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, 230), pallet_balances::Error::<Test>::InsufficientBalance);
 
-		// account_2 want to buy KITTY_ID_1 and he is rich.
+		// account_3 is rich, but only wants to pay up to 200.
 		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300);
-		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_3), ACCOUNT_ID_1, KITTY_ID_2));
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_3), KITTY_ID_2, 200), Error::<Test>::PriceTooHigh);
+		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_3), KITTY_ID_2, 230));
 
 		// check balance and kitty's new owner .
 		assert_eq!(Kitties::owner(KITTY_ID_2), Some(ACCOUNT_ID_3));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300 - 230);
 
-		// Unstake + income = 50 + 230
-		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), (50 + 230));
+		// The seller gets the price, the deposit moves to the buyer with the kitty.
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 230);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 50);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_3), 50);
 
 		System::assert_has_event(Event::Kitties(crate::Event::<Test>::MakeDeal(ACCOUNT_ID_3, ACCOUNT_ID_1, KITTY_ID_2)));
 		System::assert_has_event(Event::Kitties(crate::Event::<Test>::KittyTransfer(ACCOUNT_ID_1, ACCOUNT_ID_3, KITTY_ID_2)));
 
-		// The number of sell lists is empty
-		let mut sell_list = Kitties::sell_list(ACCOUNT_ID_1);
-		assert_eq!(sell_list.clone().len(), 0 , "Sell list is empty. ");
+		// The kitty is no longer for sale.
+		assert_eq!(Kitties::listings(KITTY_ID_2), None);
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, 230), Error::<Test>::KittyHasNotSold);
+	});
+}
+
+#[test]
+fn test_kitty_cancel_sell () {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+
+		// Nothing to cancel.
+		assert_noop!(Kitties::cancel_sell(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1), Error::<Test>::KittyHasNotSold);
+
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 10));
+
+		// Only the seller can cancel.
+		assert_noop!(Kitties::cancel_sell(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1), Error::<Test>::NotOwner);
+
+		assert_ok!(Kitties::cancel_sell(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1));
+		assert_eq!(Kitties::listings(KITTY_ID_1), None);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::SellCanceled(ACCOUNT_ID_1, KITTY_ID_1)));
+
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 10), Error::<Test>::KittyHasNotSold);
+	});
+}
+
+#[test]
+fn test_kitty_transfer_removes_listing () {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 10));
+
+		// Giving the kitty away ends the sale, the old owner can not be paid for it anymore.
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, KITTY_ID_1));
+		assert_eq!(Kitties::listings(KITTY_ID_1), None);
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 10), Error::<Test>::KittyHasNotSold);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300);
+	});
+}
+
+#[test]
+fn test_kitty_deposit() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_4: u64 = 4;
+
+		// Account 4 has no balance for the deposit.
+		assert_noop!(Kitties::create(Origin::signed(ACCOUNT_ID_4)), Error::<Test>::InsufficientDeposit);
+
+		// Each kitty reserves the deposit.
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 100);
+		assert_eq!(Kitties::kitty_deposit(1), 50);
+		assert_noop!(Kitties::create(Origin::signed(ACCOUNT_ID_1)), Error::<Test>::InsufficientDeposit);
+
+		// Breeding as well.
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_1), 1, 2), Error::<Test>::InsufficientDeposit);
+
+		// The deposit follows the kitty.
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, 2));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 50);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 50);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200);
+
+		// A new account receives the deposit with the kitty, and gives it back.
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_4, 1));
+		assert_eq!(Kitties::owner(1), Some(ACCOUNT_ID_4));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_4), 50);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_4), ACCOUNT_ID_1, 1));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 50);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_4) + Balances::reserved_balance(ACCOUNT_ID_4), 0);
+
+		// Unless the deposit is below the existential deposit.
+		ExistentialDeposit::set(&100);
+		assert_noop!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), 5, 1), Error::<Test>::DepositBelowExistentialDeposit);
+		ExistentialDeposit::set(&0);
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(Kitties::bread(Origin::signed(ACCOUNT_ID_2), 2, 3));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 150);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 100);
+
+		// Burning releases the deposit.
+		assert_noop!(Kitties::burn(Origin::signed(ACCOUNT_ID_2), 1), Error::<Test>::NotOwner);
+		assert_noop!(Kitties::burn(Origin::signed(ACCOUNT_ID_2), 5), Error::<Test>::InvalidKittyIndex);
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_1), 1, 10));
+		assert_ok!(Kitties::burn(Origin::signed(ACCOUNT_ID_1), 1));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::KittyBurned(ACCOUNT_ID_1, 1)));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 50);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_eq!(Kitties::kitties(1), None);
+		assert_eq!(Kitties::owner(1), None);
+		assert_eq!(Kitties::listings(1), None);
+		assert_eq!(Kitties::kitty_deposit(1), 0);
+		assert_eq!(Kitties::owned_kitties(ACCOUNT_ID_1).len(), 0);
+		assert_noop!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, 1), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn test_owned_kitties_index() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+
+		const KITTY_ID_1: u32 = 1;
+		const KITTY_ID_2: u32 = 2;
+		const KITTY_ID_3: u32 = 3;
+		const KITTY_ID_4: u32 = 4;
+
+		// Created and bred kitties are indexed under their owner.
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, KITTY_ID_2));
+		assert_eq!(Kitties::owned_kitties(ACCOUNT_ID_3).to_vec(), vec![KITTY_ID_1, KITTY_ID_2, KITTY_ID_3]);
+		assert_eq!(Kitties::owned_kitties(ACCOUNT_ID_2).len(), 0);
+
+		// Transfer moves the kitty between the indexes.
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_3), ACCOUNT_ID_2, KITTY_ID_1));
+		assert_eq!(Kitties::owned_kitties(ACCOUNT_ID_3).to_vec(), vec![KITTY_ID_2, KITTY_ID_3]);
+		assert_eq!(Kitties::owned_kitties(ACCOUNT_ID_2).to_vec(), vec![KITTY_ID_1]);
+
+		// So does buying.
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_3), KITTY_ID_2, 10));
+		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, 10));
+		assert_eq!(Kitties::owned_kitties(ACCOUNT_ID_3).to_vec(), vec![KITTY_ID_3]);
+		assert_eq!(Kitties::owned_kitties(ACCOUNT_ID_2).to_vec(), vec![KITTY_ID_1, KITTY_ID_2]);
+
+		// ACCOUNT_ID_2 reaches MaxKittiesOwned.
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_eq!(Kitties::owned_kitties(ACCOUNT_ID_2).to_vec(), vec![KITTY_ID_1, KITTY_ID_2, KITTY_ID_4]);
+		assert_noop!(Kitties::create(Origin::signed(ACCOUNT_ID_2)), Error::<Test>::TooManyKitties);
+		assert_noop!(Kitties::transfer(Origin::signed(ACCOUNT_ID_3), ACCOUNT_ID_2, KITTY_ID_3), Error::<Test>::TooManyKitties);
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_3), KITTY_ID_3, 10));
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), KITTY_ID_3, 10), Error::<Test>::TooManyKitties);
+		assert_eq!(Kitties::owner(KITTY_ID_3), Some(ACCOUNT_ID_3));
+	});
+}
+
+#[test]
+fn test_kitty_dutch_auction_price() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+
+		// The floor can not be above the start price.
+		assert_noop!(Kitties::to_sell_dutch(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 100, 200, 10), Error::<Test>::InvalidPriceRange);
+		assert_noop!(Kitties::to_sell_dutch(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 200, 100, 0), Error::<Test>::InvalidAuctionDuration);
+		assert_noop!(Kitties::to_sell_dutch(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 200, 100, 10), Error::<Test>::NotOwner);
+
+		assert_eq!(Kitties::current_price(KITTY_ID_1), None);
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 200, 100, 10));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::ToDutchSellList(ACCOUNT_ID_1, KITTY_ID_1, 200, 100, 172)));
+
+		// Start price.
+		assert_eq!(Kitties::current_price(KITTY_ID_1), Some(200));
+
+		// Half way.
+		setup_blocks(167);
+		assert_eq!(Kitties::current_price(KITTY_ID_1), Some(150));
+		setup_blocks(168);
+		assert_eq!(Kitties::current_price(KITTY_ID_1), Some(140));
+
+		// Floor price at the end block.
+		setup_blocks(172);
+		assert_eq!(Kitties::current_price(KITTY_ID_1), Some(100));
+
+		// Delisted afterwards.
+		setup_blocks(173);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::DutchListingExpired(KITTY_ID_1)));
+		assert_eq!(Kitties::current_price(KITTY_ID_1), None);
+		assert_eq!(Kitties::listings(KITTY_ID_1), None);
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 200), Error::<Test>::KittyHasNotSold);
+		assert_eq!(Kitties::kitties_for_sale(0, 10, crate::ListingSort::PriceAsc), vec![]);
+
+		// The kitty can be auctioned right away.
+		assert_ok!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 100, 5));
+	});
+}
+
+#[test]
+fn test_kitty_dutch_listing_replaced() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(1), 1, 200, 100, 10));
+		assert_eq!(Kitties::dutch_listing_ends(173).to_vec(), vec![1]);
+
+		// A fixed price listing replacing the dutch one is kept past its end.
+		assert_ok!(Kitties::to_sell(Origin::signed(1), 1, 150));
+		assert_eq!(Kitties::dutch_listing_ends(173).len(), 0);
+		setup_blocks(173);
+		assert_eq!(Kitties::current_price(1), Some(150));
+
+		// As is a longer dutch listing.
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(1), 1, 200, 100, 2));
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(1), 1, 200, 100, 5));
+		setup_blocks(176);
+		assert!(Kitties::listings(1).is_some());
+		setup_blocks(179);
+		assert_eq!(Kitties::listings(1), None);
+	});
+}
 
+#[test]
+fn test_kitty_dutch_relist_frees_its_end() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::create(Origin::signed(2)));
+
+		// Relisting with the same duration keeps a single entry for the end block.
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(1), 1, 200, 100, 10));
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(1), 1, 200, 100, 10));
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(1), 1, 300, 100, 10));
+		assert_eq!(Kitties::dutch_listing_ends(173).to_vec(), vec![1]);
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(2), 2, 200, 100, 10));
+		assert_eq!(Kitties::dutch_listing_ends(173).to_vec(), vec![1, 2]);
+
+		// Cancelling, transferring and burning free the entry as well.
+		assert_ok!(Kitties::cancel_sell(Origin::signed(1), 1));
+		assert_eq!(Kitties::dutch_listing_ends(173).to_vec(), vec![2]);
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(1), 1, 200, 100, 10));
+		assert_ok!(Kitties::transfer(Origin::signed(1), 3, 1));
+		assert_eq!(Kitties::dutch_listing_ends(173).to_vec(), vec![2]);
+		assert_ok!(Kitties::burn(Origin::signed(2), 2));
+		assert!(Kitties::dutch_listing_ends(173).is_empty());
+	});
+}
+
+#[test]
+fn test_kitty_dutch_auction_buy() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 200, 100, 10));
+
+		// The first buyer pays the current price.
+		setup_blocks(167);
+		assert_noop!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 149), Error::<Test>::PriceTooHigh);
+		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 200));
+		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_3));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300 - 150);
+		assert_eq!(Kitties::current_price(KITTY_ID_1), None);
+	});
+}
+
+#[test]
+fn test_kitty_english_auction() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+
+		const KITTY_ID_1: u32 = 1;
+		const KITTY_ID_2: u32 = 2;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 50);
+
+		// Only the owner can auction, and for at least one block.
+		assert_noop!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 100, 10), Error::<Test>::NotOwner);
+		assert_noop!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 100, 0), Error::<Test>::InvalidAuctionDuration);
+
+		assert_ok!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 100, 10));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::AuctionCreated(ACCOUNT_ID_1, KITTY_ID_1, 100, 172)));
+		assert_eq!(Kitties::auction_ends(172).to_vec(), vec![KITTY_ID_1]);
+
+		// The kitty is locked while auctioned.
+		assert_noop!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 100, 10), Error::<Test>::KittyInAuction);
+		assert_noop!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, KITTY_ID_1), Error::<Test>::KittyInAuction);
+		assert_noop!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 100), Error::<Test>::KittyInAuction);
+
+		// Invalid bids.
+		assert_noop!(Kitties::bid(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, 100), Error::<Test>::AuctionNotExist);
+		assert_noop!(Kitties::bid(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 100), Error::<Test>::BidOwnAuction);
+		assert_noop!(Kitties::bid(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 99), Error::<Test>::BidTooLow);
+
+		// The bid is reserved from the bidder.
+		assert_ok!(Kitties::bid(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 100));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::BidPlaced(ACCOUNT_ID_2, KITTY_ID_1, 100)));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 100);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 100);
+
+		// Outbidding refunds the previous bidder.
+		assert_noop!(Kitties::bid(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 100), Error::<Test>::BidTooLow);
+		assert_ok!(Kitties::bid(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 150));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_3), 150);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 0);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200);
+
+		// Still running one block before the end.
+		setup_blocks(171);
+		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_1));
+		assert_ok!(Kitties::bid(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 160));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_3), 0);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300);
+
+		// Settled at the end block.
+		setup_blocks(172);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::AuctionSettled(KITTY_ID_1, ACCOUNT_ID_2, 160)));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::KittyTransfer(ACCOUNT_ID_1, ACCOUNT_ID_2, KITTY_ID_1)));
+		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_2));
+		assert_eq!(Kitties::owned_kitties(ACCOUNT_ID_2).to_vec(), vec![KITTY_ID_1]);
+		// The bid is paid to the seller and the kitty deposit moves to the winner.
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 50);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200 - 160);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 50 + 160);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_eq!(Kitties::auctions(KITTY_ID_1), None);
+		assert_eq!(Kitties::auction_ends(172).len(), 0);
+		assert_noop!(Kitties::bid(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 200), Error::<Test>::AuctionNotExist);
+	});
+}
+
+#[test]
+fn test_kitty_auction_without_bids() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 100, 2));
+
+		// Settled without a winner at the end block.
+		setup_blocks(164);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::AuctionUnsold(KITTY_ID_1)));
+		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_1));
+		assert_eq!(Kitties::auctions(KITTY_ID_1), None);
+
+		// The kitty is free to move again.
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, KITTY_ID_1));
 	});
 }
 
+#[test]
+fn test_kitty_auctions_per_block_limit() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_3: u64 = 3;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+
+		// A listed kitty can not be auctioned.
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_3), 1, 10));
+		assert_noop!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_3), 1, 100, 5), Error::<Test>::KittyOnSale);
+		assert_ok!(Kitties::cancel_sell(Origin::signed(ACCOUNT_ID_3), 1));
+
+		// MaxAuctionsPerBlock is 2.
+		assert_ok!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_3), 1, 100, 5));
+		assert_ok!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_3), 2, 100, 5));
+		assert_noop!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_3), 3, 100, 5), Error::<Test>::TooManyAuctions);
+		assert_ok!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_3), 3, 100, 6));
+	});
+}
+
+#[test]
+fn test_kitty_approve_and_transfer_from() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+
+		// Only the owner can approve, and not to themself.
+		assert_noop!(Kitties::approve(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, Some(ACCOUNT_ID_2)), Error::<Test>::NotApproved);
+		assert_noop!(Kitties::approve(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, Some(ACCOUNT_ID_1)), Error::<Test>::ApproveToCaller);
+		assert_noop!(Kitties::transfer_from(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, ACCOUNT_ID_2, KITTY_ID_1), Error::<Test>::NotApproved);
+
+		assert_ok!(Kitties::approve(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, Some(ACCOUNT_ID_2)));
+		assert_eq!(Kitties::approvals(KITTY_ID_1), Some(ACCOUNT_ID_2));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::Approval(ACCOUNT_ID_1, Some(ACCOUNT_ID_2), KITTY_ID_1)));
+
+		// Revoked approvals can not transfer.
+		assert_ok!(Kitties::approve(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, None));
+		assert_eq!(Kitties::approvals(KITTY_ID_1), None);
+		assert_noop!(Kitties::transfer_from(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, ACCOUNT_ID_2, KITTY_ID_1), Error::<Test>::NotApproved);
+
+		// The approved account moves the kitty to a third account.
+		assert_ok!(Kitties::approve(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, Some(ACCOUNT_ID_2)));
+		assert_noop!(Kitties::transfer_from(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_3, ACCOUNT_ID_2, KITTY_ID_1), Error::<Test>::NotOwner);
+		assert_ok!(Kitties::transfer_from(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, ACCOUNT_ID_3, KITTY_ID_1));
+		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_3));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::KittyTransfer(ACCOUNT_ID_1, ACCOUNT_ID_3, KITTY_ID_1)));
+
+		// The approval does not survive the transfer.
+		assert_eq!(Kitties::approvals(KITTY_ID_1), None);
+		assert_noop!(Kitties::transfer_from(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_3, ACCOUNT_ID_2, KITTY_ID_1), Error::<Test>::NotApproved);
+	});
+}
+
+#[test]
+fn test_kitty_approval_for_all() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_1: u32 = 1;
+		const KITTY_ID_2: u32 = 2;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+
+		assert_noop!(Kitties::set_approval_for_all(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_1, true), Error::<Test>::ApproveToCaller);
+		assert_ok!(Kitties::set_approval_for_all(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, true));
+		assert!(Kitties::operator_approvals(ACCOUNT_ID_1, ACCOUNT_ID_2));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::ApprovalForAll(ACCOUNT_ID_1, ACCOUNT_ID_2, true)));
+
+		// An operator can transfer any kitty of the owner and approve others for it.
+		assert_ok!(Kitties::transfer_from(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, ACCOUNT_ID_3, KITTY_ID_1));
+		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_3));
+		assert_ok!(Kitties::approve(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, Some(ACCOUNT_ID_3)));
+		assert_eq!(Kitties::approvals(KITTY_ID_2), Some(ACCOUNT_ID_3));
+
+		// The operator has no rights over kitties of other owners.
+		assert_noop!(Kitties::transfer_from(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_3, ACCOUNT_ID_2, KITTY_ID_1), Error::<Test>::NotApproved);
+
+		// Revoking the operator ends its rights, the kitty approval stays.
+		assert_ok!(Kitties::set_approval_for_all(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, false));
+		assert!(!Kitties::operator_approvals(ACCOUNT_ID_1, ACCOUNT_ID_2));
+		assert_noop!(Kitties::transfer_from(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, ACCOUNT_ID_2, KITTY_ID_2), Error::<Test>::NotApproved);
+		assert_ok!(Kitties::transfer_from(Origin::signed(ACCOUNT_ID_3), ACCOUNT_ID_1, ACCOUNT_ID_3, KITTY_ID_2));
+		assert_eq!(Kitties::owner(KITTY_ID_2), Some(ACCOUNT_ID_3));
+	});
+}
+
+#[test]
+fn test_kitty_nonfungible_traits() {
+	use frame_support::traits::tokens::nonfungible::{Inspect, Mutate, Transfer};
+
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const KITTY_ID_1: u32 = 1;
+		const KITTY_ID_2: u32 = 2;
+
+		// Only the next kitty id can be minted, the deposit is reserved as for `create`.
+		assert_noop!(<Kitties as Mutate<u64>>::mint_into(&KITTY_ID_2, &ACCOUNT_ID_1), Error::<Test>::InvalidKittyIndex);
+		assert_ok!(<Kitties as Mutate<u64>>::mint_into(&KITTY_ID_1, &ACCOUNT_ID_1));
+		assert_eq!(<Kitties as Inspect<u64>>::owner(&KITTY_ID_1), Some(ACCOUNT_ID_1));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 50);
+
+		// The dna is exposed as an attribute.
+		let dna = Kitties::kitties(KITTY_ID_1).unwrap().dna;
+		assert_eq!(<Kitties as Inspect<u64>>::attribute(&KITTY_ID_1, b"dna"), Some(dna.to_vec()));
+		assert_eq!(<Kitties as Inspect<u64>>::attribute(&KITTY_ID_1, b"name"), None);
+
+		// Transfers go through the pallet, moving the deposit along.
+		assert!(<Kitties as Inspect<u64>>::can_transfer(&KITTY_ID_1));
+		assert_ok!(<Kitties as Transfer<u64>>::transfer(&KITTY_ID_1, &ACCOUNT_ID_2));
+		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_2));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 50);
+
+		// Kitties in an auction can not be transferred.
+		assert_ok!(Kitties::create_auction(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 10, 5));
+		assert!(!<Kitties as Inspect<u64>>::can_transfer(&KITTY_ID_1));
+		assert_noop!(<Kitties as Mutate<u64>>::burn_from(&KITTY_ID_1), Error::<Test>::KittyInAuction);
+
+		assert_ok!(<Kitties as Mutate<u64>>::mint_into(&KITTY_ID_2, &ACCOUNT_ID_2));
+		assert_ok!(<Kitties as Mutate<u64>>::burn_from(&KITTY_ID_2));
+		assert_eq!(<Kitties as Inspect<u64>>::owner(&KITTY_ID_2), None);
+		assert!(!<Kitties as Inspect<u64>>::can_transfer(&KITTY_ID_2));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 50);
+	});
+}
+
+#[test]
+fn test_kitty_metadata() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+
+		assert_noop!(Kitties::set_metadata(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, b"Tom".to_vec(), vec![]), Error::<Test>::NotOwner);
+		assert_noop!(Kitties::set_metadata(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, b"Tom the cat".to_vec(), vec![]), Error::<Test>::NameTooLong);
+		assert_noop!(Kitties::set_metadata(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, b"Tom".to_vec(), b"ipfs://0123456789abcdef".to_vec()), Error::<Test>::UriTooLong);
+
+		// 1 per byte on top of the kitty deposit.
+		assert_ok!(Kitties::set_metadata(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, b"Tom".to_vec(), b"ipfs://tom".to_vec()));
+		let metadata = Kitties::kitty_metadata(KITTY_ID_1).unwrap();
+		assert_eq!(metadata.name.to_vec(), b"Tom".to_vec());
+		assert_eq!(metadata.uri.to_vec(), b"ipfs://tom".to_vec());
+		assert_eq!(metadata.deposit, 13);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 50 + 13);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::MetadataSet(KITTY_ID_1, b"Tom".to_vec(), b"ipfs://tom".to_vec())));
+
+		// Shorter metadata gives back the difference.
+		assert_ok!(Kitties::set_metadata(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, b"Tom".to_vec(), vec![]));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 50 + 3);
+
+		// Empty values clear the metadata.
+		assert_ok!(Kitties::set_metadata(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, vec![], vec![]));
+		assert!(Kitties::kitty_metadata(KITTY_ID_1).is_none());
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 50);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::MetadataCleared(KITTY_ID_1)));
+
+		// Burning refunds the metadata deposit with the kitty deposit.
+		assert_ok!(Kitties::set_metadata(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, b"Tom".to_vec(), vec![]));
+		assert_ok!(Kitties::burn(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1));
+		assert!(Kitties::kitty_metadata(KITTY_ID_1).is_none());
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 100);
+	});
+}
+
+#[test]
+fn test_kitty_metadata_on_transfer() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Kitties::set_metadata(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, b"Tom".to_vec(), vec![]));
+
+		// Cleared and refunded when the kitty changes hands.
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_1), ACCOUNT_ID_2, KITTY_ID_1));
+		assert!(Kitties::kitty_metadata(KITTY_ID_1).is_none());
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 50);
+
+		// Otherwise the metadata and its deposit move with the kitty.
+		ClearMetadataOnTransfer::set(&false);
+		assert_ok!(Kitties::set_metadata(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, b"Jerry".to_vec(), vec![]));
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_2), ACCOUNT_ID_1, KITTY_ID_1));
+		assert_eq!(Kitties::kitty_metadata(KITTY_ID_1).unwrap().name.to_vec(), b"Jerry".to_vec());
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 0);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_1), 50 + 5);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200 - 5);
+	});
+}
+
+#[test]
+fn test_kitty_lease() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_1: u32 = 1;
+		const KITTY_ID_2: u32 = 2;
+		const KITTY_ID_3: u32 = 3;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_noop!(Kitties::take_lease(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 5), Error::<Test>::NoLeaseOffer);
+		assert_noop!(Kitties::offer_lease(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 2, 10), Error::<Test>::NotOwner);
+		assert_noop!(Kitties::offer_lease(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 2, 0), Error::<Test>::InvalidLeaseDuration);
+
+		assert_ok!(Kitties::offer_lease(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 2, 10));
+		assert_ok!(Kitties::offer_lease(Origin::signed(ACCOUNT_ID_3), KITTY_ID_2, 2, 10));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::LeaseOffered(ACCOUNT_ID_3, KITTY_ID_1, 2, 10)));
+
+		assert_noop!(Kitties::take_lease(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 5), Error::<Test>::LeaseOwnKitty);
+		assert_noop!(Kitties::take_lease(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 11), Error::<Test>::InvalidLeaseDuration);
+
+		// 2 per block for 5 blocks, paid to the owner.
+		assert_ok!(Kitties::take_lease(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 5));
+		assert_ok!(Kitties::take_lease(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, 5));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::LeaseTaken(ACCOUNT_ID_2, KITTY_ID_1, 10, 167)));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200 - 20);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300 - 100 + 20);
+		assert_eq!(Kitties::user_of(KITTY_ID_1), Some(ACCOUNT_ID_2));
+		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_3));
+		assert_noop!(Kitties::take_lease(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 5), Error::<Test>::KittyLeased);
+		assert_noop!(Kitties::burn(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1), Error::<Test>::KittyLeased);
+
+		// The user breeds the leased kitties, the owner can not.
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, KITTY_ID_2), Error::<Test>::NotOwner);
+		assert_ok!(Kitties::bread(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, KITTY_ID_2));
+		assert_eq!(Kitties::owner(KITTY_ID_3), Some(ACCOUNT_ID_2));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 50);
+
+		// The owner can still sell a leased kitty, the lease goes on and the offer ends.
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_3), ACCOUNT_ID_1, KITTY_ID_1));
+		assert_eq!(Kitties::user_of(KITTY_ID_1), Some(ACCOUNT_ID_2));
+		assert!(Kitties::lease_offers(KITTY_ID_1).is_none());
+
+		// Both kitties return when the lease ends.
+		setup_blocks(167);
+		assert!(Kitties::lease(KITTY_ID_1).is_none());
+		assert_eq!(Kitties::user_of(KITTY_ID_1), Some(ACCOUNT_ID_1));
+		assert_eq!(Kitties::user_of(KITTY_ID_2), Some(ACCOUNT_ID_3));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::LeaseEnded(KITTY_ID_2, ACCOUNT_ID_2)));
+		assert_noop!(Kitties::bread(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, KITTY_ID_2), Error::<Test>::NotOwner);
+
+		// The offer of kitty 2 still stands until it is canceled.
+		assert_ok!(Kitties::cancel_lease_offer(Origin::signed(ACCOUNT_ID_3), KITTY_ID_2));
+		assert_noop!(Kitties::take_lease(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, 5), Error::<Test>::NoLeaseOffer);
+	});
+}
+
+#[test]
+fn test_kitty_breed_with_sire() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_1: u32 = 1;
+		const KITTY_ID_2: u32 = 2;
+		const KITTY_ID_3: u32 = 3;
+		const KITTY_ID_4: u32 = 4;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_noop!(Kitties::breed_with_sire(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, KITTY_ID_1, 20), Error::<Test>::NotSire);
+		assert_noop!(Kitties::list_sire(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 20), Error::<Test>::NotOwner);
+
+		assert_ok!(Kitties::list_sire(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 20));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::SireListed(ACCOUNT_ID_3, KITTY_ID_1, 20)));
+		assert_noop!(Kitties::breed_with_sire(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, KITTY_ID_1, 19), Error::<Test>::PriceTooHigh);
+		assert_noop!(Kitties::breed_with_sire(Origin::signed(ACCOUNT_ID_1), KITTY_ID_2, KITTY_ID_1, 20), Error::<Test>::NotOwner);
+
+		// The fee goes to the sire owner, the child and its deposit to the breeder.
+		assert_ok!(Kitties::breed_with_sire(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, KITTY_ID_1, 20));
+		assert_eq!(Kitties::owner(KITTY_ID_3), Some(ACCOUNT_ID_2));
+		assert_eq!(Kitties::kitties(KITTY_ID_3).unwrap().parents, Some((KITTY_ID_2, KITTY_ID_1)));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200 - 50 - 20 - 50);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300 - 50 + 20);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::SireBred(ACCOUNT_ID_2, ACCOUNT_ID_3, KITTY_ID_1, KITTY_ID_3, 20)));
+
+		// The sire rests like any parent and no fee is taken for a failed breeding.
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(Kitties::breed_with_sire(Origin::signed(ACCOUNT_ID_1), KITTY_ID_4, KITTY_ID_1, 20), Error::<Test>::BreedingCooldown);
+
+		setup_blocks(167);
+		assert_noop!(Kitties::breed_with_sire(Origin::signed(ACCOUNT_ID_2), KITTY_ID_3, KITTY_ID_1, 20), Error::<Test>::RelatedParents);
+		assert_noop!(Kitties::breed_with_sire(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, KITTY_ID_1, 20), Error::<Test>::SameParentIndex);
+
+		// A sire changing hands is no longer listed.
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_3), ACCOUNT_ID_1, KITTY_ID_1));
+		assert!(Kitties::sires(KITTY_ID_1).is_none());
+		assert_ok!(Kitties::list_sire(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 20));
+		assert_noop!(Kitties::breed_with_sire(Origin::signed(ACCOUNT_ID_1), KITTY_ID_4, KITTY_ID_1, 20), Error::<Test>::BreedOwnSire);
+		assert_ok!(Kitties::cancel_sire(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1));
+		assert_noop!(Kitties::breed_with_sire(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, KITTY_ID_1, 20), Error::<Test>::NotSire);
+	});
+}
+
+#[test]
+fn test_kitty_royalty() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_eq!(Kitties::kitties(KITTY_ID_1).unwrap().creator, Some(ACCOUNT_ID_1));
+
+		// The creator selling their own kitty keeps the whole price.
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 100));
+		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 100));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 100 - 50 + 100);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200 - 100);
+
+		// Every resale pays 10% to the creator.
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 100));
+		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 100));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::RoyaltyPaid(ACCOUNT_ID_1, KITTY_ID_1, 10)));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 150 + 10);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 100 + 90);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300 - 100);
+
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 50));
+		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 50));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::RoyaltyPaid(ACCOUNT_ID_1, KITTY_ID_1, 5)));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 160 + 5);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 190 - 50);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 200 + 45);
+
+		// Without royalty the seller gets everything.
+		setup_blocks(163);
+		RoyaltyPercent::set(&Permill::zero());
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 50));
+		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 50));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 165);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 140 + 50);
+		assert!(!System::events().iter().any(|record| matches!(record.event, Event::Kitties(crate::Event::<Test>::RoyaltyPaid(..)))));
+	});
+}
+
+#[test]
+fn test_kitty_auction_royalty() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create(Origin::signed(1)));
+		assert_ok!(Kitties::transfer(Origin::signed(1), 2, 1));
+
+		// The winning bid pays 10% to the creator, the rest to the seller.
+		assert_ok!(Kitties::create_auction(Origin::signed(2), 1, 100, 10));
+		assert_ok!(Kitties::bid(Origin::signed(3), 1, 120));
+		setup_blocks(172);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::RoyaltyPaid(1, 1, 12)));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::AuctionSettled(1, 3, 120)));
+		assert_eq!(Kitties::owner(1), Some(3));
+		assert_eq!(Balances::free_balance(1), 50 + 12);
+		assert_eq!(Balances::free_balance(2), 200 + 108);
+		assert_eq!(Balances::free_balance(3), 300 - 120);
+		assert_eq!(Balances::reserved_balance(3), 50);
+	});
+}
+
+#[test]
+fn test_kitty_royalty_to_breeder() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_3: u32 = 3;
+
+		// The breeder is the creator of the child.
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::bread(Origin::signed(ACCOUNT_ID_3), 1, 2));
+		assert_eq!(Kitties::kitties(KITTY_ID_3).unwrap().creator, Some(ACCOUNT_ID_3));
+
+		assert_ok!(Kitties::transfer(Origin::signed(ACCOUNT_ID_3), ACCOUNT_ID_2, KITTY_ID_3));
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_2), KITTY_ID_3, 100));
+		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_3), KITTY_ID_3, 100));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::RoyaltyPaid(ACCOUNT_ID_3, KITTY_ID_3, 10)));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200 + 90);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300 - 150 - 100 + 10);
+	});
+}
+
+#[test]
+fn test_kitty_offer_accept_and_withdraw() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_1: u32 = 1;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_noop!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, 30, 170), Error::<Test>::OfferOwnKitty);
+		assert_noop!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 30, 162), Error::<Test>::InvalidOfferExpiry);
+		assert_noop!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_2), 2, 30, 170), Error::<Test>::InvalidKittyIndex);
+		// An offer has to reserve something, or anyone could fill the lapsing offers for free.
+		assert_noop!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 0, 170), Error::<Test>::OfferTooLow);
+		assert_noop!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 9, 170), Error::<Test>::OfferTooLow);
+
+		// Offers on an unlisted kitty reserve the amount.
+		assert_ok!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 30, 170));
+		assert_ok!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 80, 170));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::OfferMade(ACCOUNT_ID_2, KITTY_ID_1, 30, 170)));
+		assert_noop!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 40, 170), Error::<Test>::OfferExists);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 30);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_3), 80);
+
+		// Withdrawing releases the amount.
+		assert_ok!(Kitties::withdraw_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 0);
+		assert_eq!(Kitties::offer_expiries(170).to_vec(), vec![(KITTY_ID_1, ACCOUNT_ID_3)]);
+		assert_noop!(Kitties::withdraw_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1), Error::<Test>::OfferNotExist);
+
+		// Only the owner accepts, the bidder pays from the reserved amount.
+		assert_noop!(Kitties::accept_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, ACCOUNT_ID_3), Error::<Test>::NotOwner);
+		assert_noop!(Kitties::accept_offer(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, ACCOUNT_ID_2), Error::<Test>::OfferNotExist);
+		assert_ok!(Kitties::accept_offer(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, ACCOUNT_ID_3));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::OfferAccepted(ACCOUNT_ID_1, ACCOUNT_ID_3, KITTY_ID_1, 80)));
+		assert_eq!(Kitties::owner(KITTY_ID_1), Some(ACCOUNT_ID_3));
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_1), 100 - 50 + 80);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_3), 300 - 80);
+		// Only the kitty deposit stays reserved.
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_3), 50);
+		assert!(Kitties::offers(KITTY_ID_1, ACCOUNT_ID_3).is_none());
+		assert_eq!(Kitties::offer_expiries(170).len(), 0);
+	});
+}
+
+#[test]
+fn test_kitty_offer_expiry_and_burn() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_1: u64 = 1;
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+		const KITTY_ID_1: u32 = 1;
+		const KITTY_ID_2: u32 = 2;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_1)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_1, 30, 165));
+		assert_ok!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_3), KITTY_ID_1, 40, 165));
+		assert_noop!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, 40, 165), Error::<Test>::TooManyOffers);
+		assert_ok!(Kitties::make_offer(Origin::signed(ACCOUNT_ID_2), KITTY_ID_2, 40, 170));
+
+		// Offers lapse at their expiry block.
+		setup_blocks(165);
+		assert!(Kitties::offers(KITTY_ID_1, ACCOUNT_ID_2).is_none());
+		assert!(Kitties::offers(KITTY_ID_1, ACCOUNT_ID_3).is_none());
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::OfferExpired(ACCOUNT_ID_2, KITTY_ID_1)));
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 40);
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_3), 50);
+		assert_noop!(Kitties::accept_offer(Origin::signed(ACCOUNT_ID_1), KITTY_ID_1, ACCOUNT_ID_2), Error::<Test>::OfferNotExist);
+
+		// Burning the kitty releases its offers.
+		assert_ok!(Kitties::burn(Origin::signed(ACCOUNT_ID_3), KITTY_ID_2));
+		assert!(Kitties::offers(KITTY_ID_2, ACCOUNT_ID_2).is_none());
+		assert_eq!(Balances::reserved_balance(ACCOUNT_ID_2), 0);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200);
+
+		// The stale expiry entry is ignored.
+		setup_blocks(170);
+		assert_eq!(Balances::free_balance(ACCOUNT_ID_2), 200);
+	});
+}
+
+#[test]
+fn test_kitties_for_sale() {
+	use crate::{KittySale, ListingSort};
+
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		const ACCOUNT_ID_2: u64 = 2;
+		const ACCOUNT_ID_3: u64 = 3;
+
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_3)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_ok!(Kitties::create(Origin::signed(ACCOUNT_ID_2)));
+		assert_eq!(Kitties::kitties_for_sale(0, 10, ListingSort::PriceAsc), vec![]);
+
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_3), 1, 30));
+		setup_blocks(163);
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_2), 3, 10));
+		assert_ok!(Kitties::to_sell_dutch(Origin::signed(ACCOUNT_ID_3), 2, 40, 20, 2));
+		setup_blocks(164);
+		assert_ok!(Kitties::to_sell(Origin::signed(ACCOUNT_ID_2), 4, 30));
+
+		let ids = |sales: Vec<KittySale<u64, u32, u64, u64>>| sales.into_iter().map(|sale| sale.kitty_id).collect::<Vec<_>>();
+
+		// The dutch auction is at 30 half way, ties are ordered by kitty id.
+		assert_eq!(ids(Kitties::kitties_for_sale(0, 10, ListingSort::PriceAsc)), vec![3, 1, 2, 4]);
+		assert_eq!(ids(Kitties::kitties_for_sale(0, 10, ListingSort::PriceDesc)), vec![1, 2, 4, 3]);
+		assert_eq!(ids(Kitties::kitties_for_sale(0, 10, ListingSort::Newest)), vec![4, 2, 3, 1]);
+		assert_eq!(ids(Kitties::kitties_for_sale(0, 10, ListingSort::Oldest)), vec![1, 2, 3, 4]);
+		assert_eq!(Kitties::kitties_for_sale(1, 1, ListingSort::PriceAsc), vec![KittySale {
+			kitty_id: 1,
+			seller: ACCOUNT_ID_3,
+			price: 30,
+			listed_at: 162,
+			ends_at: None,
+		}]);
+
+		// Pages past the end are empty.
+		assert_eq!(ids(Kitties::kitties_for_sale(2, 10, ListingSort::PriceAsc)), vec![2, 4]);
+		assert_eq!(ids(Kitties::kitties_for_sale(4, 10, ListingSort::PriceAsc)), vec![]);
+
+		// Expired dutch auctions and sold kitties are no longer for sale.
+		setup_blocks(166);
+		assert_ok!(Kitties::to_buy(Origin::signed(ACCOUNT_ID_2), 1, 30));
+		assert_eq!(ids(Kitties::kitties_for_sale(0, 10, ListingSort::PriceAsc)), vec![3, 4]);
+	});
+}
+
+#[test]
+fn test_genesis_kitties() {
+	new_test_ext_with_kitties(vec![(1, [1; 16]), (2, [2; 16]), (2, [3; 16])]).execute_with(|| {
+		assert_eq!(Kitties::next_kitty_id(), 4);
+		assert_eq!(Kitties::owner(1), Some(1));
+		assert_eq!(Kitties::owner(3), Some(2));
+		assert_eq!(Kitties::owned_kitties(2).into_inner(), vec![2, 3]);
+		assert_eq!(Kitties::kitties(2).unwrap().dna, [2; 16]);
+		assert_eq!(Kitties::kitties(2).unwrap().creator, Some(2));
+
+		// Owners paid the usual deposit.
+		assert_eq!(Balances::reserved_balance(1), 50);
+		assert_eq!(Balances::reserved_balance(2), 100);
+
+		// Genesis kitties behave like created ones.
+		setup_blocks(2);
+		assert_ok!(Kitties::bread(Origin::signed(2), 2, 3));
+		assert_eq!(Kitties::owner(4), Some(2));
+		assert_ok!(Kitties::transfer(Origin::signed(1), 3, 1));
+		assert_eq!(Kitties::owner(1), Some(3));
+	});
+}
+
+#[test]
+fn test_transfer_batch() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::create(Origin::signed(2)));
+
+		assert_noop!(Kitties::transfer_batch(Origin::signed(3), 1, vec![1, 2, 1, 2]), Error::<Test>::BatchTooLarge);
+
+		// One kitty the caller does not own fails the whole batch.
+		assert_noop!(Kitties::transfer_batch(Origin::signed(3), 1, vec![1, 3]), Error::<Test>::NotOwner);
+		assert_eq!(Kitties::owner(1), Some(3));
+
+		assert_ok!(Kitties::transfer_batch(Origin::signed(3), 1, vec![1, 2]));
+		assert_eq!(Kitties::owner(1), Some(1));
+		assert_eq!(Kitties::owner(2), Some(1));
+		assert_eq!(Kitties::owned_kitties(1).into_inner(), vec![1, 2]);
+		assert!(Kitties::owned_kitties(3).is_empty());
+		assert_eq!(Balances::reserved_balance(1), 100);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::KittyTransfer(3, 1, 2)));
+
+		// The receiver has room for one more kitty, so neither is moved.
+		assert_ok!(Kitties::create(Origin::signed(2)));
+		assert_noop!(Kitties::transfer_batch(Origin::signed(1), 2, vec![1, 2]), Error::<Test>::TooManyKitties);
+		assert_ok!(Kitties::transfer(Origin::signed(1), 2, 1));
+		assert_eq!(Kitties::owned_kitties(2).into_inner(), vec![3, 4, 1]);
+	});
+}
+
+#[test]
+fn test_list_batch() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::create(Origin::signed(2)));
+
+		assert_noop!(Kitties::list_batch(Origin::signed(3), vec![(1, 10); 4]), Error::<Test>::BatchTooLarge);
+
+		assert_ok!(Kitties::list_batch(Origin::signed(3), vec![(1, 10), (2, 20)]));
+		assert_eq!(Kitties::current_price(1), Some(10));
+		assert_eq!(Kitties::current_price(2), Some(20));
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::ToSellList(3, 2, 20)));
+
+		// Nothing is relisted when one kitty fails.
+		assert_noop!(Kitties::list_batch(Origin::signed(3), vec![(1, 30), (3, 30)]), Error::<Test>::NotOwner);
+		assert_eq!(Kitties::current_price(1), Some(10));
+	});
+}
+
+#[test]
+fn test_force_create() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+
+		assert_noop!(Kitties::force_create(Origin::signed(1), 3, true), frame_support::error::BadOrigin);
+
+		// The owner pays the deposit of a forced kitty.
+		assert_ok!(Kitties::force_create(Origin::root(), 3, true));
+		assert_eq!(Kitties::owner(1), Some(3));
+		assert!(Kitties::is_locked(1));
+		assert_eq!(Balances::reserved_balance(3), 50);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::KittyLocked(1)));
+
+		assert_ok!(Kitties::force_create(Origin::root(), 3, false));
+		assert!(!Kitties::is_locked(2));
+	});
+}
+
+#[test]
+fn test_locked_kitty_can_not_change_hands() {
+	use frame_support::traits::tokens::nonfungible::{Inspect, Transfer};
+
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::force_create(Origin::root(), 3, true));
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::set_approval_for_all(Origin::signed(3), 1, true));
+
+		// Transfers, by the owner, an operator or the nonfungible traits.
+		assert_noop!(Kitties::transfer(Origin::signed(3), 2, 1), Error::<Test>::KittyLocked);
+		assert_noop!(Kitties::transfer_from(Origin::signed(1), 3, 2, 1), Error::<Test>::KittyLocked);
+		assert_noop!(Kitties::transfer_batch(Origin::signed(3), 2, vec![2, 1]), Error::<Test>::KittyLocked);
+		assert_eq!(Kitties::owner(2), Some(3));
+		assert!(!<Kitties as Inspect<u64>>::can_transfer(&1));
+		assert_noop!(<Kitties as Transfer<u64>>::transfer(&1, &2), Error::<Test>::KittyLocked);
+
+		// Approvals can only be revoked.
+		assert_noop!(Kitties::approve(Origin::signed(3), 1, Some(2)), Error::<Test>::KittyLocked);
+		assert_ok!(Kitties::approve(Origin::signed(3), 1, None));
+
+		// Sales, auctions and offers.
+		assert_noop!(Kitties::to_sell(Origin::signed(3), 1, 10), Error::<Test>::KittyLocked);
+		assert_noop!(Kitties::to_sell_dutch(Origin::signed(3), 1, 20, 10, 5), Error::<Test>::KittyLocked);
+		assert_noop!(Kitties::list_batch(Origin::signed(3), vec![(2, 10), (1, 10)]), Error::<Test>::KittyLocked);
+		assert_noop!(Kitties::create_auction(Origin::signed(3), 1, 10, 5), Error::<Test>::KittyLocked);
+		assert_noop!(Kitties::make_offer(Origin::signed(2), 1, 10, 170), Error::<Test>::KittyLocked);
+
+		// The owner can still burn it.
+		assert_ok!(Kitties::burn(Origin::signed(3), 1));
+		assert!(!Kitties::is_locked(1));
+	});
+}
+
+#[test]
+fn test_lock_kitty() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::to_sell(Origin::signed(3), 1, 10));
+		assert_ok!(Kitties::approve(Origin::signed(3), 1, Some(1)));
+		assert_ok!(Kitties::make_offer(Origin::signed(2), 1, 10, 170));
+
+		assert_noop!(Kitties::lock(Origin::signed(2), 1), Error::<Test>::NotOwner);
+
+		// Locking takes the kitty off sale and revokes its approval.
+		assert_ok!(Kitties::lock(Origin::signed(3), 1));
+		assert!(Kitties::is_locked(1));
+		assert_eq!(Kitties::listings(1), None);
+		assert_eq!(Kitties::approvals(1), None);
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::KittyLocked(1)));
+
+		// It can not be undone.
+		assert_noop!(Kitties::lock(Origin::signed(3), 1), Error::<Test>::KittyLocked);
+
+		// Standing offers can not be accepted and lapse.
+		assert_noop!(Kitties::to_buy(Origin::signed(2), 1, 10), Error::<Test>::KittyHasNotSold);
+		assert_noop!(Kitties::transfer_from(Origin::signed(1), 3, 2, 1), Error::<Test>::NotApproved);
+		assert_noop!(Kitties::accept_offer(Origin::signed(3), 1, 2), Error::<Test>::KittyLocked);
+		setup_blocks(170);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Kitties::owner(1), Some(3));
+
+		// Kitties in an auction can not be locked.
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::create_auction(Origin::signed(3), 2, 10, 5));
+		assert_noop!(Kitties::lock(Origin::signed(3), 2), Error::<Test>::KittyInAuction);
+	});
+}
+
+#[test]
+fn test_collections() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_noop!(Kitties::create_collection(Origin::signed(1), 0, 10), Error::<Test>::InvalidMaxSupply);
+		assert_noop!(Kitties::create_collection(Origin::signed(4), 2, 10), pallet_balances::Error::<Test>::InsufficientBalance);
+		assert_ok!(Kitties::create_collection(Origin::signed(1), 2, 10));
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::CollectionCreated(1, 1, 2, 10)));
+		assert_eq!(Kitties::next_collection_id(), 2);
+		assert_eq!(Balances::reserved_balance(1), 20);
+
+		// Minters pay the issuer, the issuer mints for free.
+		assert_ok!(Kitties::mint(Origin::signed(2), 1));
+		assert_eq!(Kitties::owner(1), Some(2));
+		assert_eq!(Kitties::kitties(1).unwrap().collection, 1);
+		assert_eq!(Balances::free_balance(2), 200 - 10 - 50);
+		assert_eq!(Balances::free_balance(1), 80 + 10);
+		assert_ok!(Kitties::mint(Origin::signed(1), 1));
+		assert_eq!(Balances::free_balance(1), 90 - 50);
+		assert_eq!(Kitties::kitties_in_collection(1), vec![1, 2]);
+
+		// The supply cap counts burned kitties.
+		assert_ok!(Kitties::burn(Origin::signed(2), 1));
+		assert_eq!(Kitties::kitties_in_collection(1), vec![2]);
+		assert_noop!(Kitties::mint(Origin::signed(3), 1), Error::<Test>::CollectionSoldOut);
+		assert_eq!(Kitties::collections(1).unwrap().minted, 2);
+
+		assert_noop!(Kitties::mint(Origin::signed(3), 2), Error::<Test>::CollectionNotFound);
+		assert_noop!(Kitties::mint(Origin::signed(3), 0), Error::<Test>::CollectionNotFound);
+
+		// Created and bred kitties join the default collection.
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		setup_blocks(163);
+		assert_ok!(Kitties::bread(Origin::signed(3), 3, 4));
+		assert_eq!(Kitties::kitties(5).unwrap().collection, 0);
+		assert_eq!(Kitties::kitties_in_collection(0), vec![3, 4, 5]);
+	});
+}
+
+#[test]
+fn test_collection_metadata() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create_collection(Origin::signed(1), 2, 10));
+
+		assert_noop!(Kitties::set_collection_metadata(Origin::signed(2), 1, b"cats".to_vec(), vec![]), Error::<Test>::NotCollectionIssuer);
+		assert_noop!(Kitties::set_collection_metadata(Origin::signed(1), 2, b"cats".to_vec(), vec![]), Error::<Test>::CollectionNotFound);
+		assert_noop!(Kitties::set_collection_metadata(Origin::signed(1), 1, b"fancy cats".to_vec(), vec![]), Error::<Test>::NameTooLong);
+
+		// The issuer reserves a deposit per byte.
+		assert_ok!(Kitties::set_collection_metadata(Origin::signed(1), 1, b"cats".to_vec(), b"ipfs://c".to_vec()));
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::CollectionMetadataSet(1, b"cats".to_vec(), b"ipfs://c".to_vec())));
+		assert_eq!(Kitties::collection_metadata(1).unwrap().name.into_inner(), b"cats".to_vec());
+		assert_eq!(Balances::reserved_balance(1), 20 + 12);
+
+		// Clearing it releases the deposit.
+		assert_ok!(Kitties::set_collection_metadata(Origin::signed(1), 1, vec![], vec![]));
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::CollectionMetadataCleared(1)));
+		assert_eq!(Kitties::collection_metadata(1), None);
+		assert_eq!(Balances::reserved_balance(1), 20);
+	});
+}
+
+#[test]
+fn test_destroy_collection() {
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create_collection(Origin::signed(1), 2, 10));
+		assert_ok!(Kitties::set_collection_metadata(Origin::signed(1), 1, b"cats".to_vec(), b"ipfs://c".to_vec()));
+		assert_ok!(Kitties::mint(Origin::signed(2), 1));
+
+		// Only the issuer destroys a collection, once its kitties are burned.
+		assert_noop!(Kitties::destroy_collection(Origin::signed(2), 1), Error::<Test>::NotCollectionIssuer);
+		assert_noop!(Kitties::destroy_collection(Origin::signed(1), 1), Error::<Test>::CollectionNotEmpty);
+		assert_noop!(Kitties::destroy_collection(Origin::signed(1), 2), Error::<Test>::CollectionNotFound);
+		assert_ok!(Kitties::burn(Origin::signed(2), 1));
+
+		// The deposits of the collection and its metadata are released.
+		assert_ok!(Kitties::destroy_collection(Origin::signed(1), 1));
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::CollectionDestroyed(1, 1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Kitties::collections(1), None);
+		assert_eq!(Kitties::collection_metadata(1), None);
+		assert_noop!(Kitties::mint(Origin::signed(2), 1), Error::<Test>::CollectionNotFound);
+
+		// Its id is not reused.
+		assert_ok!(Kitties::create_collection(Origin::signed(1), 2, 10));
+		assert_eq!(Kitties::next_collection_id(), 3);
+	});
+}
+
+/// A kitty whose dna expresses the legendary rarity.
+const LEGENDARY_DNA: [u8; 16] = [0, 0, 0, 0, 0, 0, 250, 250, 0, 0, 0, 0, 0, 0, 0, 0];
+
+#[test]
+fn test_staking_rewards() {
+	use frame_support::traits::Currency;
+
+	new_test_ext_with_kitties(vec![(1, [0; 16]), (2, LEGENDARY_DNA)]).execute_with(|| {
+		let pot = Kitties::account_id();
+		Balances::make_free_balance_be(&pot, 1_000);
+		setup_blocks(10);
+		assert_ok!(Kitties::stake_kitty(Origin::signed(1), 1));
+		assert_ok!(Kitties::stake_kitty(Origin::signed(2), 2));
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::KittyStaked(2, 2)));
+		assert_eq!(Kitties::pending_reward(1), Some(0));
+
+		// A legendary kitty earns ten times the reward of a common one.
+		setup_blocks(30);
+		assert_eq!(Kitties::pending_reward(1), Some(20));
+		assert_eq!(Kitties::pending_reward(2), Some(200));
+
+		assert_noop!(Kitties::unstake_kitty(Origin::signed(1), 2), Error::<Test>::NotOwner);
+		assert_ok!(Kitties::unstake_kitty(Origin::signed(2), 2));
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::KittyUnstaked(2, 2, 200)));
+		assert_eq!(Balances::free_balance(2), 150 + 200);
+		assert_eq!(Balances::free_balance(pot), 800);
+		assert_eq!(Kitties::pending_reward(2), None);
+		assert_noop!(Kitties::unstake_kitty(Origin::signed(2), 2), Error::<Test>::KittyNotStaked);
+
+		// Rewards keep accruing, staking again starts over.
+		setup_blocks(110);
+		assert_eq!(Kitties::pending_reward(1), Some(100));
+		assert_ok!(Kitties::stake_kitty(Origin::signed(2), 2));
+		setup_blocks(150);
+		assert_ok!(Kitties::unstake_kitty(Origin::signed(1), 1));
+		assert_ok!(Kitties::unstake_kitty(Origin::signed(2), 2));
+		assert_eq!(Balances::free_balance(1), 50 + 140);
+		assert_eq!(Balances::free_balance(2), 350 + 400);
+		assert_eq!(Balances::free_balance(pot), 260);
+	});
+}
+
+#[test]
+fn test_staking_rewards_capped_by_pot() {
+	use frame_support::traits::Currency;
+
+	new_test_ext_with_kitties(vec![(2, LEGENDARY_DNA)]).execute_with(|| {
+		let pot = Kitties::account_id();
+		Balances::make_free_balance_be(&pot, 30);
+		setup_blocks(1);
+		assert_ok!(Kitties::stake_kitty(Origin::signed(2), 1));
+		setup_blocks(11);
+		assert_eq!(Kitties::pending_reward(1), Some(100));
+
+		// The pot pays what it has.
+		assert_ok!(Kitties::unstake_kitty(Origin::signed(2), 1));
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::KittyUnstaked(2, 1, 30)));
+		assert_eq!(Balances::free_balance(2), 150 + 30);
+		assert_eq!(Balances::free_balance(pot), 0);
+
+		// An empty pot still lets the kitty go.
+		assert_ok!(Kitties::stake_kitty(Origin::signed(2), 1));
+		setup_blocks(20);
+		assert_ok!(Kitties::unstake_kitty(Origin::signed(2), 1));
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::KittyUnstaked(2, 1, 0)));
+		assert_eq!(Balances::free_balance(2), 180);
+	});
+}
+
+#[test]
+fn test_staked_kitty_can_not_change_hands() {
+	use frame_support::traits::tokens::nonfungible::Inspect;
+
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::to_sell(Origin::signed(3), 1, 10));
+		assert_ok!(Kitties::approve(Origin::signed(3), 1, Some(1)));
+
+		assert_noop!(Kitties::stake_kitty(Origin::signed(2), 1), Error::<Test>::NotOwner);
+
+		// Staking takes the kitty off sale and revokes its approval.
+		assert_ok!(Kitties::stake_kitty(Origin::signed(3), 1));
+		assert_eq!(Kitties::listings(1), None);
+		assert_eq!(Kitties::approvals(1), None);
+		assert_noop!(Kitties::stake_kitty(Origin::signed(3), 1), Error::<Test>::KittyStaked);
+
+		assert_noop!(Kitties::transfer(Origin::signed(3), 2, 1), Error::<Test>::KittyStaked);
+		assert_noop!(Kitties::transfer_batch(Origin::signed(3), 2, vec![2, 1]), Error::<Test>::KittyStaked);
+		assert!(!<Kitties as Inspect<u64>>::can_transfer(&1));
+		assert_noop!(Kitties::to_sell(Origin::signed(3), 1, 10), Error::<Test>::KittyStaked);
+		assert_noop!(Kitties::to_sell_dutch(Origin::signed(3), 1, 20, 10, 5), Error::<Test>::KittyStaked);
+		assert_noop!(Kitties::list_batch(Origin::signed(3), vec![(2, 10), (1, 10)]), Error::<Test>::KittyStaked);
+		assert_noop!(Kitties::create_auction(Origin::signed(3), 1, 10, 5), Error::<Test>::KittyStaked);
+		assert_noop!(Kitties::burn(Origin::signed(3), 1), Error::<Test>::KittyStaked);
+
+		// Kitties in an auction can not be staked.
+		assert_ok!(Kitties::create_auction(Origin::signed(3), 2, 10, 5));
+		assert_noop!(Kitties::stake_kitty(Origin::signed(3), 2), Error::<Test>::KittyInAuction);
+
+		// Once unstaked it changes hands again.
+		assert_ok!(Kitties::unstake_kitty(Origin::signed(3), 1));
+		assert_ok!(Kitties::transfer(Origin::signed(3), 2, 1));
+		assert_eq!(Kitties::owner(1), Some(2));
+	});
+}
+
+#[test]
+fn test_kitty_queries() {
+	use crate::KittyInfo;
+
+	new_test_ext().execute_with(|| {
+		setup_blocks(162);
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::create(Origin::signed(3)));
+		assert_ok!(Kitties::create(Origin::signed(2)));
+		assert_ok!(Kitties::to_sell(Origin::signed(3), 2, 40));
+
+		let kitty = Kitties::kitties(1).unwrap();
+		assert_eq!(Kitties::kitty_info(1), Some(KittyInfo {
+			kitty_id: 1,
+			owner: 3,
+			dna: kitty.dna,
+			parents: None,
+			generation: 0,
+			next_breed_block: 162,
+			creator: Some(3),
+			traits: crate::genes::traits(&kitty),
+		}));
+		assert_eq!(Kitties::kitty_info(4), None);
+
+		let ids = |kitties: Vec<KittyInfo<u64, u32, u64>>| kitties.into_iter().map(|kitty| kitty.kitty_id).collect::<Vec<_>>();
+		assert_eq!(ids(Kitties::kitties_of(&3)), vec![1, 2]);
+		assert_eq!(ids(Kitties::kitties_of(&1)), vec![]);
+
+		assert_eq!(Kitties::kitty_sale(2).map(|sale| sale.price), Some(40));
+		assert_eq!(Kitties::kitty_sale(1), None);
+
+		assert_ok!(Kitties::set_metadata(Origin::signed(3), 1, b"Tom".to_vec(), b"ipfs://t".to_vec()));
+		assert_eq!(Kitties::metadata_info(1), Some(crate::KittyMetadataInfo { name: b"Tom".to_vec(), uri: b"ipfs://t".to_vec() }));
+		assert_eq!(Kitties::metadata_info(2), None);
+
+		// Burned kitties are no longer counted.
+		assert_eq!(Kitties::kitties_count(), 3);
+		assert_ok!(Kitties::burn(Origin::signed(2), 3));
+		assert_eq!(Kitties::kitties_count(), 2);
+	});
+}
+
+const DNA_1: [u8; 16] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+const DNA_2: [u8; 16] = [101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116];
+
+#[test]
+fn test_commit_reveal_breed() {
+	new_test_ext_with_kitties(vec![(3, DNA_1), (3, DNA_2), (2, DNA_1)]).execute_with(|| {
+		use sp_runtime::traits::Hash;
+
+		setup_blocks(162);
+		let salt = [7; 32];
+		let salt_hash = BlakeTwo256::hash_of(&salt);
+
+		// Only the user of both kitties can commit.
+		assert_noop!(Kitties::commit_breed(Origin::signed(3), 1, 3, salt_hash), Error::<Test>::NotOwner);
+		assert_noop!(Kitties::commit_breed(Origin::signed(3), 1, 1, salt_hash), Error::<Test>::SameParentIndex);
+
+		// The deposit of the child is reserved at commit time.
+		assert_ok!(Kitties::commit_breed(Origin::signed(3), 1, 2, salt_hash));
+		assert_eq!(Balances::reserved_balance(3), 150);
+		System::assert_last_event(Event::Kitties(crate::Event::<Test>::BreedCommitted(3, 1, 2, 164)));
+		assert_eq!(Kitties::breed_commit_expiries(169).into_inner(), vec![3]);
+		assert_noop!(Kitties::commit_breed(Origin::signed(3), 1, 2, salt_hash), Error::<Test>::BreedCommitExists);
+
+		// The salt is revealed after the delay.
+		assert_noop!(Kitties::reveal_breed(Origin::signed(3), salt), Error::<Test>::RevealTooEarly);
+		setup_blocks(164);
+		assert_noop!(Kitties::reveal_breed(Origin::signed(3), [8; 32]), Error::<Test>::InvalidReveal);
+		assert_noop!(Kitties::reveal_breed(Origin::signed(2), salt), Error::<Test>::NoBreedCommit);
+
+		assert_ok!(Kitties::reveal_breed(Origin::signed(3), salt));
+		assert_eq!(Kitties::owner(4), Some(3));
+		assert_eq!(Kitties::kitties(4).unwrap().parents, Some((1, 2)));
+		assert_eq!(Kitties::kitties(1).unwrap().next_breed_block, 164 + 5);
+		assert_eq!(Balances::reserved_balance(3), 150);
+		assert_eq!(Kitties::breed_commits(3), None);
+		assert!(Kitties::breed_commit_expiries(169).is_empty());
+
+		assert_noop!(Kitties::reveal_breed(Origin::signed(3), salt), Error::<Test>::NoBreedCommit);
+	});
+}
+
+#[test]
+fn test_reveal_breed_checks_parents_again() {
+	new_test_ext_with_kitties(vec![(3, DNA_1), (3, DNA_2)]).execute_with(|| {
+		use sp_runtime::traits::Hash;
+
+		setup_blocks(162);
+		let salt = [7; 32];
+		assert_ok!(Kitties::commit_breed(Origin::signed(3), 1, 2, BlakeTwo256::hash_of(&salt)));
+
+		// A parent given away after the commit can not be bred.
+		assert_ok!(Kitties::transfer(Origin::signed(3), 1, 2));
+		setup_blocks(164);
+		assert_noop!(Kitties::reveal_breed(Origin::signed(3), salt), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn test_breed_commit_expires() {
+	new_test_ext_with_kitties(vec![(3, DNA_1), (3, DNA_2)]).execute_with(|| {
+		use sp_runtime::traits::Hash;
+
+		setup_blocks(162);
+		let salt = [7; 32];
+		assert_ok!(Kitties::commit_breed(Origin::signed(3), 1, 2, BlakeTwo256::hash_of(&salt)));
+		assert_eq!(Balances::reserved_balance(3), 150);
+
+		// Still revealable in the block before the expiry.
+		setup_blocks(168);
+		assert!(Kitties::breed_commits(3).is_some());
+
+		// The commit lapses and the deposit is refunded.
+		setup_blocks(169);
+		assert_eq!(Kitties::breed_commits(3), None);
+		assert_eq!(Balances::reserved_balance(3), 100);
+		System::assert_has_event(Event::Kitties(crate::Event::<Test>::BreedCommitExpired(3)));
+		assert_noop!(Kitties::reveal_breed(Origin::signed(3), salt), Error::<Test>::NoBreedCommit);
+		assert_eq!(Kitties::next_kitty_id(), 3);
+
+		// The breeder can commit again.
+		assert_ok!(Kitties::commit_breed(Origin::signed(3), 1, 2, BlakeTwo256::hash_of(&salt)));
+	});
+}
+
+#[test]
+fn test_reveal_breed_dna_unknown_at_commit() {
+	// Commit to the same breeding, then reveal `salt` with `hash` as the seeding block hash.
+	let reveal = |hash: H256, salt: [u8; 32]| {
+		new_test_ext_with_kitties(vec![(3, DNA_1), (3, DNA_2)]).execute_with(|| {
+			use sp_runtime::traits::Hash;
+
+			setup_blocks(162);
+			assert_ok!(Kitties::commit_breed(Origin::signed(3), 1, 2, BlakeTwo256::hash_of(&salt)));
+			setup_blocks(164);
+			frame_system::BlockHash::<Test>::insert(163, hash);
+			assert_ok!(Kitties::reveal_breed(Origin::signed(3), salt));
+			Kitties::kitties(3).unwrap().dna
+		})
+	};
+
+	// Everything known at commit time is the same, the hash of the block before the reveal
+	// window decides the child.
+	assert_eq!(reveal(H256::repeat_byte(1), [7; 32]), reveal(H256::repeat_byte(1), [7; 32]));
+	assert_ne!(reveal(H256::repeat_byte(1), [7; 32]), reveal(H256::repeat_byte(2), [7; 32]));
+	// The salt is mixed in, so an author can not precompute it from the hash alone.
+	assert_ne!(reveal(H256::repeat_byte(1), [7; 32]), reveal(H256::repeat_byte(1), [8; 32]));
+}
+
+#[test]
+fn test_reveal_breed_dna_fixed_at_commit() {
+	// Commit, then reveal in `block` while the randomness of that block is `seed`.
+	let reveal_in = |block: u64, seed: H256| {
+		new_test_ext_with_kitties(vec![(3, DNA_1), (3, DNA_2)]).execute_with(|| {
+			use sp_runtime::traits::Hash;
+
+			setup_blocks(162);
+			assert_ok!(Kitties::commit_breed(Origin::signed(3), 1, 2, BlakeTwo256::hash_of(&[7; 32])));
+			setup_blocks(block);
+			FixedRandomSeed::set(&Some(seed));
+			assert_ok!(Kitties::reveal_breed(Origin::signed(3), [7; 32]));
+			FixedRandomSeed::set(&None);
+			Kitties::kitties(3).unwrap().dna
+		})
+	};
+
+	// The breeder can not wait for a block with a better child.
+	assert_eq!(reveal_in(164, H256::repeat_byte(1)), reveal_in(168, H256::repeat_byte(2)));
+}
+
 #[test]
 fn test_random() {
 	new_test_ext().execute_with(|| {
@@ -216,11 +1944,13 @@ fn test_random() {
 	});
 }
 
+// Run blocks from the current block number up to `blocks`.
 fn setup_blocks(blocks: u64) {
 	let mut parent_hash = System::parent_hash();
-	for i in 1..(blocks + 1) {
+	for i in (System::block_number() + 1)..(blocks + 1) {
 		System::initialize(&i, &parent_hash, &Default::default(), frame_system::InitKind::Full);
 		RandomnessCollectiveFlip::on_initialize(i);
+		Kitties::on_initialize(i);
 		let header = System::finalize();
 		parent_hash = header.hash();
 		System::set_block_number(*header.number());
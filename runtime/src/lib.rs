@@ -49,6 +49,12 @@ pub use pallet_poe;
 
 // Include pallet of my ocw
 pub use pallet_ocw;
+
+/// Include pallet of my kitties
+pub use pallet_kitties;
+
+#[cfg(test)]
+mod tests;
 use frame_support::pallet_prelude::Encode;
 
 parameter_types! {
@@ -64,6 +70,75 @@ impl pallet_poe::Config for Runtime {
 }
 
 
+parameter_types! {
+	// Balance reserved from the owner for every kitty.
+	pub const MaxStakeBalance: Balance = 1_000_000;
+	// Maximum number of kitties a single account can own.
+	pub const MaxKittiesOwned: u32 = 100;
+	// Maximum number of kitty auctions ending in the same block.
+	pub const MaxAuctionsPerBlock: u32 = 50;
+	// Breeding cooldown of a generation 0 kitty.
+	pub const BreedCooldown: BlockNumber = 10 * MINUTES;
+	// Maximum number of kitty leases ending in the same block.
+	pub const MaxLeasesPerBlock: u32 = 50;
+	// Maximum number of kitty offers lapsing in the same block.
+	pub const MaxOffersPerBlock: u32 = 50;
+	// Smallest kitty offer, offers reserve at least this much.
+	pub const MinOfferAmount: Balance = 1_000_000;
+	// Maximum kitty name and metadata uri lengths in bytes.
+	pub const MaxNameLength: u32 = 32;
+	pub const MaxUriLength: u32 = 256;
+	// Balance reserved from the owner for every byte of kitty metadata.
+	pub const MetadataDepositPerByte: Balance = 10_000;
+	// Balance reserved from the issuer of a kitty collection until it is destroyed.
+	pub const CollectionDeposit: Balance = 1_000_000;
+	// A new owner starts without the metadata of the previous one.
+	pub const ClearMetadataOnTransfer: bool = true;
+	// Share of every kitty sale paid to its creator.
+	pub const RoyaltyPercent: Permill = Permill::from_percent(5);
+	// Blocks between committing to a breeding and revealing it, and the window to reveal in.
+	// The window stays well below `BlockHashCount`, the seeding block hash must still be stored.
+	pub const RevealDelay: BlockNumber = 5;
+	pub const RevealTimeout: BlockNumber = 10 * MINUTES;
+	// Maximum number of breeding commits lapsing in the same block.
+	pub const MaxBreedCommitsPerBlock: u32 = 50;
+	// Maximum number of kitties transferred or listed in one batch.
+	pub const MaxBatchSize: u32 = 20;
+	// Pot account staking rewards are paid from, funded at genesis by the chain spec, and the
+	// reward of a common kitty per block.
+	pub const KittiesPalletId: frame_support::PalletId = frame_support::PalletId(*b"py/kitty");
+	pub const StakingRewardPerBlock: Balance = 100;
+}
+
+/// Configure the pallet-kitties in pallets/kitties.
+impl pallet_kitties::Config for Runtime {
+	type Event = Event;
+	type Randomness = RandomnessCollectiveFlip;
+	type KittyIndex = u32;
+	type Currency = Balances;
+	type MaxStakeBalance = MaxStakeBalance;
+	type MaxKittiesOwned = MaxKittiesOwned;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type BreedCooldown = BreedCooldown;
+	type MaxLeasesPerBlock = MaxLeasesPerBlock;
+	type MaxOffersPerBlock = MaxOffersPerBlock;
+	type MinOfferAmount = MinOfferAmount;
+	type MaxNameLength = MaxNameLength;
+	type MaxUriLength = MaxUriLength;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type CollectionDeposit = CollectionDeposit;
+	type ClearMetadataOnTransfer = ClearMetadataOnTransfer;
+	type RoyaltyPercent = RoyaltyPercent;
+	type RevealDelay = RevealDelay;
+	type RevealTimeout = RevealTimeout;
+	type MaxBreedCommitsPerBlock = MaxBreedCommitsPerBlock;
+	type MaxBatchSize = MaxBatchSize;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type PalletId = KittiesPalletId;
+	type StakingRewardPerBlock = StakingRewardPerBlock;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
+}
+
 /// For pallet-ocw
 impl pallet_ocw::Config for Runtime {
 	type AuthorityId = pallet_ocw::crypto::TestAuthId;
@@ -376,6 +451,8 @@ construct_runtime!(
 		PoeModule: pallet_poe::{Pallet, Call, Storage, Event<T>},
 		// Add Ocw for my course.
 		OcwDemo: pallet_ocw::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
+		// Add kitties for my course.
+		Kitties: pallet_kitties::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -533,6 +610,40 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, u32, Balance, BlockNumber> for Runtime {
+		fn kitties_for_sale(
+			start: u32,
+			limit: u32,
+			sort: pallet_kitties::ListingSort,
+		) -> Vec<pallet_kitties::KittySale<AccountId, u32, Balance, BlockNumber>> {
+			Kitties::kitties_for_sale(start, limit, sort)
+		}
+
+		fn kitty(kitty_id: u32) -> Option<pallet_kitties::KittyInfo<AccountId, u32, BlockNumber>> {
+			Kitties::kitty_info(kitty_id)
+		}
+
+		fn kitties_of(owner: AccountId) -> Vec<pallet_kitties::KittyInfo<AccountId, u32, BlockNumber>> {
+			Kitties::kitties_of(&owner)
+		}
+
+		fn kitty_metadata(kitty_id: u32) -> Option<pallet_kitties::KittyMetadataInfo> {
+			Kitties::metadata_info(kitty_id)
+		}
+
+		fn kitty_traits(kitty_id: u32) -> Option<pallet_kitties::genes::KittyTraits> {
+			Kitties::kitty_traits(kitty_id)
+		}
+
+		fn listing(kitty_id: u32) -> Option<pallet_kitties::KittySale<AccountId, u32, Balance, BlockNumber>> {
+			Kitties::kitty_sale(kitty_id)
+		}
+
+		fn kitties_count() -> u32 {
+			Kitties::kitties_count()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -570,6 +681,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
 			add_benchmark!(params, batches, pallet_template, TemplateModule);
 			add_benchmark!(params, batches, pallet_poe, PoeModule);
+			add_benchmark!(params, batches, pallet_kitties, Kitties);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok((batches, storage_info))
@@ -0,0 +1,53 @@
+use crate::*;
+use frame_support::assert_ok;
+
+const ALICE: [u8; 32] = [1u8; 32];
+const BOB: [u8; 32] = [2u8; 32];
+const INITIAL_BALANCE: Balance = 1 << 60;
+
+fn account(raw: [u8; 32]) -> AccountId {
+	AccountId::from(raw)
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(account(ALICE), INITIAL_BALANCE), (account(BOB), INITIAL_BALANCE)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+// Create, breed and trade kitties through the runtime configuration.
+#[test]
+fn kitties_create_breed_and_trade_works() {
+	new_test_ext().execute_with(|| {
+		let alice = account(ALICE);
+		let bob = account(BOB);
+
+		// Alice creates two kitties, each one reserves the stake.
+		assert_ok!(Kitties::create(Origin::signed(alice.clone())));
+		assert_ok!(Kitties::create(Origin::signed(alice.clone())));
+		assert_eq!(Kitties::next_kitty_id(), 3);
+		assert_eq!(Balances::reserved_balance(&alice), 2 * MaxStakeBalance::get());
+
+		// kitty1 + kitty2 = kitty3
+		assert_ok!(Kitties::bread(Origin::signed(alice.clone()), 1, 2));
+		assert_eq!(Kitties::next_kitty_id(), 4);
+		assert_eq!(Kitties::owner(3), Some(alice.clone()));
+		assert!(Kitties::kitties(3).is_some());
+
+		// Alice sells kitty3 and bob buys it.
+		assert_ok!(Kitties::to_sell(Origin::signed(alice.clone()), 3, 1_000));
+		assert_ok!(Kitties::to_buy(Origin::signed(bob.clone()), 3, 1_000));
+		assert_eq!(Kitties::owner(3), Some(bob.clone()));
+		assert_eq!(Balances::free_balance(&bob), INITIAL_BALANCE - 1_000);
+		// The kitty deposit moved with the kitty.
+		assert_eq!(Balances::reserved_balance(&alice), 2 * MaxStakeBalance::get());
+		assert_eq!(Balances::reserved_balance(&bob), MaxStakeBalance::get());
+		assert_eq!(Kitties::listings(3), None);
+	});
+}
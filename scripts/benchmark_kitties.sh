@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+# This script is meant to be run on Unix/Linux based systems, on reference hardware
+set -e
+
+echo "*** Generating the weights of pallet-kitties"
+
+cd $(dirname ${BASH_SOURCE[0]})/..
+
+cargo build --release --features runtime-benchmarks
+
+./target/release/node-template benchmark \
+	--chain dev \
+	--execution=wasm \
+	--wasm-execution=compiled \
+	--pallet pallet_kitties \
+	--extrinsic '*' \
+	--steps 20 \
+	--repeat 50 \
+	--template=.maintain/frame-weight-template.hbs \
+	--output=./pallets/kitties/src/weights.rs